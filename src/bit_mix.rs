@@ -5,21 +5,46 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+//! Bit mixing functions which scramble the bits of a 64-bit value such that
+//! a small change of the input changes about half of the output bits. They are
+//! useful as hash functions for integer keys, as the output functions of
+//! counter-based generators and for obfuscating integer IDs.
+//!
+//! [stafford_mix13](stafford_mix13), [lea_mix64](lea_mix64), [rrxmrrxmsx](rrxmrrxmsx)
+//! and [xnasam](xnasam) are bijections on 64-bit values and each of them has an
+//! inverse (e.g., [xnasam_inverse](xnasam_inverse)) which recovers the input from the
+//! output. [stafford_mix04](stafford_mix04) returns only 32 bits and can't be inverted.
+//! Every function is available for `i64` and, with a `_u64` suffix, for `u64`
+//! values, which gives the same bits.
+//!
+//! The results of these functions are guaranteed to stay the same across
+//! releases of this crate.
+
+/// David Stafford's <a href=http://zimbry.blogspot.com/2011/09/better-bit-mixing-improving-on.html>Mix13</a>
+/// variant of MurmurHash3's 64-bit finalizer. This is also the output function of
+/// [SplitMix64](crate::pseudo_random::SplitMix64) and Java's `SplittableRandom`.
 #[inline]
-pub (crate) const fn stafford_mix13(mut v: i64) -> i64 {
+pub const fn stafford_mix13(mut v: i64) -> i64 {
     v = (v ^ (v as u64 >> 30) as i64).wrapping_mul(0xbf58476d1ce4e5b9u64 as i64);
     v = (v ^ (v as u64 >> 27) as i64).wrapping_mul(0x94d049bb133111ebu64 as i64);
     v ^ (v as u64 >> 31) as i64
 }
 
+/// The upper 32 bits of David Stafford's
+/// <a href=http://zimbry.blogspot.com/2011/09/better-bit-mixing-improving-on.html>Mix04</a>
+/// variant of MurmurHash3's 64-bit finalizer, which Java's `SplittableRandom` uses
+/// for `nextInt()`.
 #[inline]
-pub (crate) const fn stafford_mix04(mut v: i64) -> i32 {
+pub const fn stafford_mix04(mut v: i64) -> i32 {
     v = (v ^ (v as u64 >> 33) as i64).wrapping_mul(0x62a9d9ed799705f5i64);
     (((v ^ (v as u64 >> 28) as i64).wrapping_mul(0xcb24d0a5c88c35b3u64 as i64)) as u64 >> 32) as i32
 }
 
+/// Pelle Evensen's
+/// <a href=https://mostlymangling.blogspot.com/2019/01/better-stronger-mixer-and-test-procedure.html>rrxmrrxmsx_0</a>
+/// mixer.
 #[inline]
-pub (crate) const fn rrxmrrxmsx(mut v: i64) -> i64 {
+pub const fn rrxmrrxmsx(mut v: i64) -> i64 {
     v ^= ((v as u64 >> 25) as i64 | (v << 39)) ^ ((v as u64 >> 50) as i64 | (v << 14));
     v = v.wrapping_mul(0xa24baed4963ee407u64 as i64);
     v ^= ((v as u64 >> 24) as i64 | (v << 40)) ^ ((v as u64 >> 49) as i64 | (v << 15));
@@ -27,8 +52,12 @@ pub (crate) const fn rrxmrrxmsx(mut v: i64) -> i64 {
     v ^ (v as u64 >> 28) as i64
 }
 
+/// Pelle Evensen's
+/// <a href=https://mostlymangling.blogspot.com/2020/01/nasam-not-another-strange-acronym-mixer.html>xNASAM</a>
+/// mixer, which is strong enough to turn even a simple counter into a random
+/// stream.
 #[inline]
-pub (crate) const fn xnasam(mut v: i64) -> i64 {
+pub const fn xnasam(mut v: i64) -> i64 {
     v ^= 0x6a09e667f3bcc909i64;
     v ^= ((v as u64 >> 25) as i64 | (v << 39)) ^ ((v as u64 >> 47) as i64 | (v << 17));
     v = v.wrapping_mul(0x9e6c63d0676a9a99u64 as i64);
@@ -38,12 +67,184 @@ pub (crate) const fn xnasam(mut v: i64) -> i64 {
 }
 
 #[inline]
-pub (crate) const fn lea_mix64(mut v: i64) -> i64 {
+pub (crate) const fn lea_mix32(mut v: i32) -> i32 {
+    v = (v ^ (v as u32 >> 16) as i32).wrapping_mul(0xd36d884bu32 as i32);
+    v = (v ^ (v as u32 >> 16) as i32).wrapping_mul(0xd36d884bu32 as i32);
+    v ^ (v as u32 >> 16) as i32
+}
+
+#[inline]
+pub (crate) const fn murmur_mix32(mut v: i32) -> i32 {
+    v = (v ^ (v as u32 >> 16) as i32).wrapping_mul(0x85ebca6bu32 as i32);
+    v = (v ^ (v as u32 >> 13) as i32).wrapping_mul(0xc2b2ae35u32 as i32);
+    v ^ (v as u32 >> 16) as i32
+}
+
+#[inline]
+pub (crate) const fn murmur_mix64(mut v: i64) -> i64 {
+    v = (v ^ (v as u64 >> 33) as i64).wrapping_mul(0xff51afd7ed558ccdu64 as i64);
+    v = (v ^ (v as u64 >> 33) as i64).wrapping_mul(0xc4ceb9fe1a85ec53u64 as i64);
+    v ^ (v as u64 >> 33) as i64
+}
+
+/// Doug Lea's 64-bit mixing function which JDK 17 uses in its LXM family of
+/// generators.
+#[inline]
+pub const fn lea_mix64(mut v: i64) -> i64 {
     v = (v ^ (v as u64 >> 32) as i64).wrapping_mul(0xdaba0b6eb09322e3u64 as i64);
     v = (v ^ (v as u64 >> 32) as i64).wrapping_mul(0xdaba0b6eb09322e3u64 as i64);
     v ^ (v as u64 >> 32) as i64
 }
 
+/*
+ * The multiplicative inverses (modulo 2^64) of the multipliers of the
+ * bijective mixers.
+ */
+const STAFFORD13_INV1: i64 = mod_inverse(0xbf58476d1ce4e5b9u64 as i64);
+const STAFFORD13_INV2: i64 = mod_inverse(0x94d049bb133111ebu64 as i64);
+const RRXMRRXMSX_INV1: i64 = mod_inverse(0xa24baed4963ee407u64 as i64);
+const RRXMRRXMSX_INV2: i64 = mod_inverse(0x9fb21c651e98df25u64 as i64);
+const XNASAM_INV1: i64 = mod_inverse(0x9e6c63d0676a9a99u64 as i64);
+const XNASAM_INV2: i64 = mod_inverse(0x9e6d62d06f6a9a9bu64 as i64);
+const LEA64_INV: i64 = mod_inverse(0xdaba0b6eb09322e3u64 as i64);
+
+// the inverse of an odd m modulo 2^64 by Newton's method, each iteration
+// doubles the number of correct low bits (m itself has 3 of them)
+const fn mod_inverse(m: i64) -> i64 {
+    let mut inv = m;
+    let mut i = 0;
+    while i < 5 {
+        inv = inv.wrapping_mul(2i64.wrapping_sub(m.wrapping_mul(inv)));
+        i += 1;
+    }
+    inv
+}
+
+// the inverse of v ^ (v >>> shift)
+#[inline]
+const fn unxorshift(v: i64, shift: u32) -> i64 {
+    let mut x = v;
+    let mut s = shift;
+    while s < 64 {
+        x ^= (v as u64 >> s) as i64;
+        s += shift;
+    }
+    x
+}
+
+// The inverse of v ^ (v >>> 23) ^ (v >>> 51). With the shift matrix S and
+// N = S^23 + S^51 we have N^3 = 0 and N^2 = S^46 (over GF(2)), so that the
+// inverse of I + N is I + N + N^2.
+#[inline]
+const fn unxorshift_23_51(v: i64) -> i64 {
+    let u = v as u64;
+    (u ^ (u >> 23) ^ (u >> 51) ^ (u >> 46)) as i64
+}
+
+// The inverse of v ^ (v >>> a) ^ (v >>> b) for rotations by a and b. With
+// the rotation matrix R we have L = I + R^a + R^b and L^64 = I (over GF(2)),
+// so that the inverse of L is L^63, the product of L^(2^k) = I + R^(a 2^k)
+// + R^(b 2^k) for k = 0 .. 5.
+#[inline]
+const fn unxor_rotations(mut v: i64, a: u32, b: u32) -> i64 {
+    let mut k = 0;
+    while k < 6 {
+        v ^= v.rotate_right((a << k) % 64) ^ v.rotate_right((b << k) % 64);
+        k += 1;
+    }
+    v
+}
+
+/// The inverse of [stafford_mix13](stafford_mix13), i.e.,
+/// `stafford_mix13_inverse(stafford_mix13(v)) == v` for all `v`.
+#[inline]
+pub const fn stafford_mix13_inverse(mut v: i64) -> i64 {
+    v = unxorshift(v, 31).wrapping_mul(STAFFORD13_INV2);
+    v = unxorshift(v, 27).wrapping_mul(STAFFORD13_INV1);
+    unxorshift(v, 30)
+}
+
+/// The inverse of [rrxmrrxmsx](rrxmrrxmsx), i.e.,
+/// `rrxmrrxmsx_inverse(rrxmrrxmsx(v)) == v` for all `v`.
+#[inline]
+pub const fn rrxmrrxmsx_inverse(mut v: i64) -> i64 {
+    v = unxorshift(v, 28).wrapping_mul(RRXMRRXMSX_INV2);
+    v = unxor_rotations(v, 24, 49).wrapping_mul(RRXMRRXMSX_INV1);
+    unxor_rotations(v, 25, 50)
+}
+
+/// The inverse of [xnasam](xnasam), i.e., `xnasam_inverse(xnasam(v)) == v`
+/// for all `v`.
+#[inline]
+pub const fn xnasam_inverse(mut v: i64) -> i64 {
+    v = unxorshift_23_51(v).wrapping_mul(XNASAM_INV2);
+    v = unxorshift_23_51(v).wrapping_mul(XNASAM_INV1);
+    unxor_rotations(v, 25, 47) ^ 0x6a09e667f3bcc909i64
+}
+
+/// The inverse of [lea_mix64](lea_mix64), i.e.,
+/// `lea_mix64_inverse(lea_mix64(v)) == v` for all `v`.
+#[inline]
+pub const fn lea_mix64_inverse(mut v: i64) -> i64 {
+    v = unxorshift(v, 32).wrapping_mul(LEA64_INV);
+    v = unxorshift(v, 32).wrapping_mul(LEA64_INV);
+    unxorshift(v, 32)
+}
+
+/// [stafford_mix13](stafford_mix13) for `u64` values.
+#[inline]
+pub const fn stafford_mix13_u64(v: u64) -> u64 {
+    stafford_mix13(v as i64) as u64
+}
+
+/// [stafford_mix13_inverse](stafford_mix13_inverse) for `u64` values.
+#[inline]
+pub const fn stafford_mix13_inverse_u64(v: u64) -> u64 {
+    stafford_mix13_inverse(v as i64) as u64
+}
+
+/// [stafford_mix04](stafford_mix04) for `u64` values.
+#[inline]
+pub const fn stafford_mix04_u64(v: u64) -> u32 {
+    stafford_mix04(v as i64) as u32
+}
+
+/// [rrxmrrxmsx](rrxmrrxmsx) for `u64` values.
+#[inline]
+pub const fn rrxmrrxmsx_u64(v: u64) -> u64 {
+    rrxmrrxmsx(v as i64) as u64
+}
+
+/// [rrxmrrxmsx_inverse](rrxmrrxmsx_inverse) for `u64` values.
+#[inline]
+pub const fn rrxmrrxmsx_inverse_u64(v: u64) -> u64 {
+    rrxmrrxmsx_inverse(v as i64) as u64
+}
+
+/// [xnasam](xnasam) for `u64` values.
+#[inline]
+pub const fn xnasam_u64(v: u64) -> u64 {
+    xnasam(v as i64) as u64
+}
+
+/// [xnasam_inverse](xnasam_inverse) for `u64` values.
+#[inline]
+pub const fn xnasam_inverse_u64(v: u64) -> u64 {
+    xnasam_inverse(v as i64) as u64
+}
+
+/// [lea_mix64](lea_mix64) for `u64` values.
+#[inline]
+pub const fn lea_mix64_u64(v: u64) -> u64 {
+    lea_mix64(v as i64) as u64
+}
+
+/// [lea_mix64_inverse](lea_mix64_inverse) for `u64` values.
+#[inline]
+pub const fn lea_mix64_inverse_u64(v: u64) -> u64 {
+    lea_mix64_inverse(v as i64) as u64
+}
+
 #[cfg(test)]
 mod bit_mix_tests {
     use super::*;
@@ -96,6 +297,62 @@ mod bit_mix_tests {
         assert_eq!(l5, -1588154649i32);
     }
 
+    #[test]
+    fn test_lea_mix32() {
+        let i1 = -750078390i32;
+        let i2 = 1523418742i32;
+        let i3 = 2030114021i32;
+        let i1 = lea_mix32(i1);
+        let i2 = lea_mix32(i2);
+        let i3 = lea_mix32(i3);
+        println!("i1: {}", i1);
+        println!("i2: {}", i2);
+        println!("i3: {}", i3);
+        assert_eq!(i1, 436104783i32);
+        assert_eq!(i2, 817963848i32);
+        assert_eq!(i3, 108653725i32);
+    }
+
+    #[test]
+    fn test_murmur_mix32() {
+        let i1 = -750078390i32;
+        let i2 = 1523418742i32;
+        let i3 = 2030114021i32;
+        let i1 = murmur_mix32(i1);
+        let i2 = murmur_mix32(i2);
+        let i3 = murmur_mix32(i3);
+        println!("i1: {}", i1);
+        println!("i2: {}", i2);
+        println!("i3: {}", i3);
+        assert_eq!(i1, 75544389i32);
+        assert_eq!(i2, -818494539i32);
+        assert_eq!(i3, 2058977115i32);
+    }
+
+    #[test]
+    fn test_murmur_mix64() {
+        let l1 = -3222165538581252362i64;
+        let l2 = -6575083715474529190i64;
+        let l3 = 5852023251876651789i64;
+        let l4 = -2105427755758183442i64;
+        let l5 = 8768027636157682880i64;
+        let l1 = murmur_mix64(l1);
+        let l2 = murmur_mix64(l2);
+        let l3 = murmur_mix64(l3);
+        let l4 = murmur_mix64(l4);
+        let l5 = murmur_mix64(l5);
+        println!("l1: {}", l1);
+        println!("l2: {}", l2);
+        println!("l3: {}", l3);
+        println!("l4: {}", l4);
+        println!("l5: {}", l5);
+        assert_eq!(l1, -6344739227073327545i64);
+        assert_eq!(l2, -8909659979788977208i64);
+        assert_eq!(l3, 2582413061186138695i64);
+        assert_eq!(l4, 6258625026734202206i64);
+        assert_eq!(l5, -4037498706444172362i64);
+    }
+
     #[test]
     fn test_lea_mix64() {
         let l1 = -3222165538581252362i64;
@@ -167,4 +424,47 @@ mod bit_mix_tests {
         assert_eq!(l4, -8485343714015871830i64);
         assert_eq!(l5, 4496690204852887599i64);
     }
+
+    #[test]
+    fn test_mod_inverse() {
+        for &m in &[
+            0xbf58476d1ce4e5b9u64 as i64,
+            0xdaba0b6eb09322e3u64 as i64,
+            3i64,
+            -1i64,
+        ] {
+            assert_eq!(m.wrapping_mul(mod_inverse(m)), 1i64);
+        }
+    }
+
+    #[test]
+    fn test_inverses() {
+        let mut v = 0x0123456789abcdefi64;
+        for i in 0..10_000i64 {
+            v = v.wrapping_mul(0x5851f42d4c957f2di64).wrapping_add(i);
+            for &x in &[v, i, -i, i64::MIN + i] {
+                assert_eq!(stafford_mix13_inverse(stafford_mix13(x)), x);
+                assert_eq!(rrxmrrxmsx_inverse(rrxmrrxmsx(x)), x);
+                assert_eq!(xnasam_inverse(xnasam(x)), x);
+                assert_eq!(lea_mix64_inverse(lea_mix64(x)), x);
+                assert_eq!(stafford_mix13(stafford_mix13_inverse(x)), x);
+                assert_eq!(xnasam(xnasam_inverse(x)), x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_u64() {
+        let l1 = -3222165538581252362i64;
+        let u1 = l1 as u64;
+        assert_eq!(stafford_mix13_u64(u1), stafford_mix13(l1) as u64);
+        assert_eq!(stafford_mix04_u64(u1), stafford_mix04(l1) as u32);
+        assert_eq!(rrxmrrxmsx_u64(u1), rrxmrrxmsx(l1) as u64);
+        assert_eq!(xnasam_u64(u1), xnasam(l1) as u64);
+        assert_eq!(lea_mix64_u64(u1), lea_mix64(l1) as u64);
+        assert_eq!(stafford_mix13_inverse_u64(stafford_mix13_u64(u1)), u1);
+        assert_eq!(rrxmrrxmsx_inverse_u64(rrxmrrxmsx_u64(u1)), u1);
+        assert_eq!(xnasam_inverse_u64(xnasam_u64(u1)), u1);
+        assert_eq!(lea_mix64_inverse_u64(lea_mix64_u64(u1)), u1);
+    }
 }
@@ -0,0 +1,533 @@
+// Copyright 2022 Stefan Zobel
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! The counter-based generators Philox and Threefry of
+//! <a href=https://www.thesalmons.org/john/random123/papers/random123sc11.pdf>Salmon
+//! et al. (2011)</a>. Each output block is a keyed bijection of a counter, so
+//! the random numbers for any counter can be computed directly.
+
+use crate::pseudo_random::{expand_seed, Advance, PseudoRandom, Seedable};
+use crate::split_mix64::SplitMix64;
+use crate::xor_shift_128plus::XorShift128Plus;
+use core::convert::TryInto;
+
+/*
+ * The multipliers and the Weyl sequence increments of the key schedule of
+ * Philox4x32 and Philox4x64 from the Random123 library.
+ */
+const PHILOX_M4X32: [i32; 2] = [0xd2511f53u32 as i32, 0xcd9e8d57u32 as i32];
+const PHILOX_W32: [i32; 2] = [0x9e3779b9u32 as i32, 0xbb67ae85u32 as i32];
+const PHILOX_M4X64: [i64; 2] = [0xd2e7470ee14c6c93u64 as i64, 0xca5a826395121157u64 as i64];
+const PHILOX_W64: [i64; 2] = [0x9e3779b97f4a7c15u64 as i64, 0xbb67ae8584caa73bu64 as i64];
+
+// the rotation constants of Threefry2x64 and the parity constant of its key
+// schedule (taken from Skein)
+const THREEFRY_R2X64: [u32; 8] = [16, 42, 12, 31, 16, 32, 24, 21];
+const THREEFRY_PARITY: i64 = 0x1bd11bdaa9fc1a22i64;
+
+#[inline]
+fn mul_hi_lo32(a: i32, b: i32) -> (i32, i32) {
+    let p = (a as u32 as u64) * (b as u32 as u64);
+    ((p >> 32) as i32, p as i32)
+}
+
+#[inline]
+fn mul_hi_lo64(a: i64, b: i64) -> (i64, i64) {
+    let p = (a as u64 as u128) * (b as u64 as u128);
+    ((p >> 64) as i64, p as i64)
+}
+
+// adds n to the 128-bit counter whose least significant word comes first
+#[inline]
+fn add_counter32(c: &mut [i32; 4], n: u128) {
+    let mut v = 0u128;
+    for (i, &w) in c.iter().enumerate() {
+        v |= (w as u32 as u128) << (32 * i);
+    }
+    v = v.wrapping_add(n);
+    for (i, w) in c.iter_mut().enumerate() {
+        *w = (v >> (32 * i)) as i32;
+    }
+}
+
+// adds n to the counter whose least significant word comes first
+#[inline]
+fn add_counter64<const N: usize>(c: &mut [i64; N], n: u128) {
+    let mut carry = n;
+    for w in c.iter_mut() {
+        if carry == 0u128 {
+            break;
+        }
+        let sum = (*w as u64 as u128) + (carry as u64 as u128);
+        *w = sum as i64;
+        carry = (carry >> 64) + (sum >> 64);
+    }
+}
+
+// the methods and trait implementations that all counter-based generators
+// share, $t must provide block() and internal_new(), $longs is the number
+// of calls to next_long() that a block lasts
+macro_rules! impl_counter_based {
+    ($t:ident, $word:ty, $key_len:expr, $ctr_len:expr, $blk_len:expr, $longs:expr, $add:ident, $seed_len:expr) => {
+        impl $t {
+            #[doc = concat!("Creates a new [", stringify!($t), "](", stringify!($t), ") with a random key and a counter of zero.")]
+            #[cfg(feature = "std")]
+            #[inline]
+            pub fn new() -> Self {
+                $t::internal_new(&mut XorShift128Plus::new())
+            }
+
+            #[doc = concat!("Creates a new [", stringify!($t), "](", stringify!($t), ") with a key derived from the given `seed`")]
+            /// and a counter of zero.
+            #[inline]
+            pub fn new_from(seed: i64) -> Self {
+                $t::internal_new(&mut XorShift128Plus::new_from(seed))
+            }
+
+            #[doc = concat!("Creates a new [", stringify!($t), "](", stringify!($t), ") with the given `key` whose first")]
+            /// output is the first word of the block for `counter`. The first word of
+            /// `counter` is its least significant one.
+            #[inline]
+            pub fn new_from_key(key: [$word; $key_len], counter: [$word; $ctr_len]) -> Self {
+                $t {
+                    key,
+                    counter,
+                    block: $t::block(key, counter),
+                    pos: 0usize,
+                }
+            }
+
+            /// Returns the key of this generator.
+            #[inline]
+            pub fn key(&self) -> [$word; $key_len] {
+                self.key
+            }
+
+            /// Returns the counter of the block that the next output is taken from.
+            #[inline]
+            pub fn counter(&self) -> [$word; $ctr_len] {
+                self.counter
+            }
+
+            /// Continues with the first word of the block for `counter`, which makes
+            /// it possible to jump to any position of the stream directly.
+            #[inline]
+            pub fn set_counter(&mut self, counter: [$word; $ctr_len]) {
+                self.counter = counter;
+                self.block = $t::block(self.key, counter);
+                self.pos = 0usize;
+            }
+
+            /// Returns the next complete block and moves on to the block for the
+            /// next counter. The rest of a partially consumed block is skipped.
+            #[inline]
+            pub fn next_block(&mut self) -> [$word; $blk_len] {
+                if self.pos != 0usize {
+                    self.skip(0u128, $blk_len - self.pos);
+                }
+                let block = self.block;
+                self.skip(1u128, 0usize);
+                block
+            }
+
+            #[inline]
+            fn next_word(&mut self) -> $word {
+                let word = self.block[self.pos];
+                self.skip(0u128, 1usize);
+                word
+            }
+
+            // moves the stream position by the given number of complete blocks
+            // and further words (at most a block) ahead
+            #[inline]
+            fn skip(&mut self, blocks: u128, words: usize) {
+                let pos = self.pos + words;
+                let blocks = blocks + (pos / $blk_len) as u128;
+                self.pos = pos % $blk_len;
+                if blocks != 0u128 {
+                    $add(&mut self.counter, blocks);
+                    self.block = $t::block(self.key, self.counter);
+                }
+            }
+        }
+
+        impl Advance for $t {
+            #[inline]
+            fn advance(&mut self, n: u128) {
+                let per_block = $longs as u128;
+                let words = (n % per_block) as usize * ($blk_len / $longs);
+                self.skip(n / per_block, words);
+            }
+        }
+
+        impl Seedable for $t {
+            type Seed = [u8; $seed_len];
+
+            /// Uses the seed as the key and starts with a counter of zero.
+            #[inline]
+            fn from_seed(seed: Self::Seed) -> Self {
+                let mut key = [0 as $word; $key_len];
+                let size = $seed_len / $key_len;
+                for (i, w) in key.iter_mut().enumerate() {
+                    *w = <$word>::from_le_bytes(seed[size * i..size * (i + 1)].try_into().unwrap());
+                }
+                $t::new_from_key(key, [0 as $word; $ctr_len])
+            }
+
+            #[inline]
+            fn seed_from_u64(state: u64) -> Self {
+                Self::from_seed(expand_seed(SplitMix64::new_from(state as i64)))
+            }
+
+            #[cfg(feature = "std")]
+            #[inline]
+            fn from_entropy() -> Self {
+                Self::from_seed(crate::seed::system_seed())
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl Default for $t {
+            #[inline]
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    };
+}
+
+/// The counter-based `Philox4x32-10` generator of
+/// <a href=https://www.thesalmons.org/john/random123/papers/random123sc11.pdf>Salmon
+/// et al. (2011)</a> with a 64-bit key and a 128-bit counter. Each counter
+/// yields a block of four 32-bit words, i.e., two calls to
+/// [next_long()](PseudoRandom::next_long).
+///
+/// This generator has a period of 2<sup>130</sup> words for each of the
+/// 2<sup>64</sup> keys.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Philox4x32 {
+    key: [i32; 2],
+    // the counter of the current block
+    counter: [i32; 4],
+    // the current block and the index of its next unused word
+    block: [i32; 4],
+    pos: usize,
+}
+
+impl PseudoRandom for Philox4x32 {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        let hi = (self.next_word() as i64) << 32;
+        hi | (self.next_word() as u32 as i64)
+    }
+
+    #[inline]
+    fn next_int(&mut self) -> i32 {
+        self.next_word()
+    }
+}
+
+impl Philox4x32 {
+    /// Returns the block of `Philox4x32-10` for the given `key` and `counter`.
+    /// It is the same as `philox4x32(counter, key)` of the Random123 library.
+    pub fn block(key: [i32; 2], counter: [i32; 4]) -> [i32; 4] {
+        let mut k = key;
+        let mut x = counter;
+        for _ in 0..10 {
+            let (hi0, lo0) = mul_hi_lo32(PHILOX_M4X32[0], x[0]);
+            let (hi1, lo1) = mul_hi_lo32(PHILOX_M4X32[1], x[2]);
+            x = [hi1 ^ x[1] ^ k[0], lo1, hi0 ^ x[3] ^ k[1], lo0];
+            k[0] = k[0].wrapping_add(PHILOX_W32[0]);
+            k[1] = k[1].wrapping_add(PHILOX_W32[1]);
+        }
+        x
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let k = seeder.next_long();
+        Philox4x32::new_from_key([k as i32, (k >> 32) as i32], [0i32; 4])
+    }
+}
+
+impl_counter_based!(Philox4x32, i32, 2, 4, 4, 2, add_counter32, 8);
+
+/// The counter-based `Philox4x64-10` generator of
+/// <a href=https://www.thesalmons.org/john/random123/papers/random123sc11.pdf>Salmon
+/// et al. (2011)</a> with a 128-bit key and a 256-bit counter. Each counter
+/// yields a block of four 64-bit words.
+///
+/// This generator has a period of 2<sup>258</sup> words for each of the
+/// 2<sup>128</sup> keys.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Philox4x64 {
+    key: [i64; 2],
+    // the counter of the current block
+    counter: [i64; 4],
+    // the current block and the index of its next unused word
+    block: [i64; 4],
+    pos: usize,
+}
+
+impl PseudoRandom for Philox4x64 {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        self.next_word()
+    }
+}
+
+impl Philox4x64 {
+    /// Returns the block of `Philox4x64-10` for the given `key` and `counter`.
+    /// It is the same as `philox4x64(counter, key)` of the Random123 library.
+    pub fn block(key: [i64; 2], counter: [i64; 4]) -> [i64; 4] {
+        let mut k = key;
+        let mut x = counter;
+        for _ in 0..10 {
+            let (hi0, lo0) = mul_hi_lo64(PHILOX_M4X64[0], x[0]);
+            let (hi1, lo1) = mul_hi_lo64(PHILOX_M4X64[1], x[2]);
+            x = [hi1 ^ x[1] ^ k[0], lo1, hi0 ^ x[3] ^ k[1], lo0];
+            k[0] = k[0].wrapping_add(PHILOX_W64[0]);
+            k[1] = k[1].wrapping_add(PHILOX_W64[1]);
+        }
+        x
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let key = [seeder.next_long(), seeder.next_long()];
+        Philox4x64::new_from_key(key, [0i64; 4])
+    }
+}
+
+impl_counter_based!(Philox4x64, i64, 2, 4, 4, 4, add_counter64, 16);
+
+/// The counter-based `Threefry2x64-20` generator of
+/// <a href=https://www.thesalmons.org/john/random123/papers/random123sc11.pdf>Salmon
+/// et al. (2011)</a>, which is based on the Threefish block cipher, with a
+/// 128-bit key and a 128-bit counter. Each counter yields a block of two
+/// 64-bit words.
+///
+/// This generator has a period of 2<sup>129</sup> words for each of the
+/// 2<sup>128</sup> keys.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Threefry2x64 {
+    key: [i64; 2],
+    // the counter of the current block
+    counter: [i64; 2],
+    // the current block and the index of its next unused word
+    block: [i64; 2],
+    pos: usize,
+}
+
+impl PseudoRandom for Threefry2x64 {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        self.next_word()
+    }
+}
+
+impl Threefry2x64 {
+    /// Returns the block of `Threefry2x64-20` for the given `key` and `counter`.
+    /// It is the same as `threefry2x64(counter, key)` of the Random123 library.
+    pub fn block(key: [i64; 2], counter: [i64; 2]) -> [i64; 2] {
+        let ks = [key[0], key[1], THREEFRY_PARITY ^ key[0] ^ key[1]];
+        let mut x0 = counter[0].wrapping_add(ks[0]);
+        let mut x1 = counter[1].wrapping_add(ks[1]);
+        for round in 0..20 {
+            x0 = x0.wrapping_add(x1);
+            x1 = x1.rotate_left(THREEFRY_R2X64[round % 8]) ^ x0;
+            // inject the key after every four rounds
+            if round % 4 == 3 {
+                let s = round / 4 + 1;
+                x0 = x0.wrapping_add(ks[s % 3]);
+                x1 = x1.wrapping_add(ks[(s + 1) % 3]).wrapping_add(s as i64);
+            }
+        }
+        [x0, x1]
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let key = [seeder.next_long(), seeder.next_long()];
+        Threefry2x64::new_from_key(key, [0i64; 2])
+    }
+}
+
+impl_counter_based!(Threefry2x64, i64, 2, 2, 2, 2, add_counter64, 16);
+
+#[cfg(test)]
+mod counter_based_tests {
+    use super::*;
+    use crate::test_util::check_advance;
+
+    // The expected blocks for the all-zero and the all-one inputs and for the
+    // digits of pi are the official known-answer vectors of the Random123
+    // library (kat_vectors) for all three generators.
+
+    #[test]
+    fn test_philox4x32() {
+        assert_eq!(
+            Philox4x32::block([0i32; 2], [0i32; 4]),
+            [
+                0x6627e8d5u32 as i32,
+                0xe169c58du32 as i32,
+                0xbc57ac4cu32 as i32,
+                0x9b00dbd8u32 as i32
+            ]
+        );
+        assert_eq!(
+            Philox4x32::block([-1i32; 2], [-1i32; 4]),
+            [
+                0x408f276di32,
+                0x41c83b0ei32,
+                0xa20bc7c6u32 as i32,
+                0x6d5451fdi32
+            ]
+        );
+        assert_eq!(
+            Philox4x32::block(
+                [0xa4093822u32 as i32, 0x299f31d0i32],
+                [
+                    0x243f6a88i32,
+                    0x85a308d3u32 as i32,
+                    0x13198a2ei32,
+                    0x03707344i32
+                ]
+            ),
+            [
+                0xd16cfe09u32 as i32,
+                0x94fdccebu32 as i32,
+                0x5001e420i32,
+                0x24126ea1i32
+            ]
+        );
+    }
+
+    #[test]
+    fn test_philox4x64() {
+        assert_eq!(
+            Philox4x64::block([0i64; 2], [0i64; 4]),
+            [
+                0x16554d9eca36314ci64,
+                0xdb20fe9d672d0fdcu64 as i64,
+                0xd7e772cee186176bu64 as i64,
+                0x7e68b68aec7ba23bi64,
+            ]
+        );
+        assert_eq!(
+            Philox4x64::block([-1i64; 2], [-1i64; 4]),
+            [
+                0x87b092c3013fe90bu64 as i64,
+                0x438c3c67be8d0224i64,
+                0x9cc7d7c69cd777b6u64 as i64,
+                0xa09caebf594f0ba0u64 as i64,
+            ]
+        );
+        assert_eq!(
+            Philox4x64::block(
+                [0x452821e638d01377i64, 0xbe5466cf34e90c6cu64 as i64],
+                [
+                    0x243f6a8885a308d3i64,
+                    0x13198a2e03707344i64,
+                    0xa4093822299f31d0u64 as i64,
+                    0x082efa98ec4e6c89i64,
+                ]
+            ),
+            [
+                0xa528f45403e61d95u64 as i64,
+                0x38c72dbd566e9788i64,
+                0xa5a1610e72fd18b5u64 as i64,
+                0x57bd43b5e52b7fe6i64,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_threefry2x64() {
+        assert_eq!(
+            Threefry2x64::block([0i64; 2], [0i64; 2]),
+            [0xc2b6e3a8c2c69865u64 as i64, 0x6f81ed42f350084di64]
+        );
+        assert_eq!(
+            Threefry2x64::block([-1i64; 2], [-1i64; 2]),
+            [0xe02cb7c4d95d277au64 as i64, 0xd06633d0893b8b68u64 as i64]
+        );
+        assert_eq!(
+            Threefry2x64::block(
+                [0xa4093822299f31d0u64 as i64, 0x082efa98ec4e6c89i64],
+                [0x243f6a8885a308d3i64, 0x13198a2e03707344i64]
+            ),
+            [0x263c7d30bb0f0af1i64, 0x56be8361d3311526i64]
+        );
+    }
+
+    #[test]
+    fn test_stream() {
+        // the stream is the concatenation of the blocks for the counters 0, 1, ...
+        let key = [0x1234i64, 0x5678i64];
+        let mut prng = Philox4x64::new_from_key(key, [0i64; 4]);
+        for c in 0..3i64 {
+            for &w in &Philox4x64::block(key, [c, 0i64, 0i64, 0i64]) {
+                assert_eq!(prng.next_long(), w);
+            }
+        }
+        assert_eq!(prng.counter(), [3i64, 0i64, 0i64, 0i64]);
+        let mut prng = Philox4x32::new_from_key([7i32, 9i32], [0i32; 4]);
+        let b = Philox4x32::block([7i32, 9i32], [0i32; 4]);
+        assert_eq!(prng.next_int(), b[0]);
+        assert_eq!(
+            prng.next_long(),
+            ((b[1] as i64) << 32) | (b[2] as u32 as i64)
+        );
+        assert_eq!(prng.next_int(), b[3]);
+        assert_eq!(prng.counter(), [1i32, 0i32, 0i32, 0i32]);
+    }
+
+    #[test]
+    fn test_counter_carry() {
+        let key = [1i64, 2i64];
+        let mut prng = Threefry2x64::new_from_key(key, [-1i64, 0i64]);
+        prng.next_block();
+        assert_eq!(prng.counter(), [0i64, 1i64]);
+        assert_eq!(prng.next_long(), Threefry2x64::block(key, [0i64, 1i64])[0]);
+        let mut prng = Philox4x32::new_from_key([1i32, 2i32], [-1i32, -1i32, 0i32, 0i32]);
+        prng.advance(2u128);
+        assert_eq!(prng.counter(), [0i32, 0i32, 1i32, 0i32]);
+        let mut prng = Philox4x64::new_from_key(key, [-1i64; 4]);
+        prng.advance(4u128);
+        assert_eq!(prng.counter(), [0i64; 4]);
+    }
+
+    #[test]
+    fn test_set_counter() {
+        // the random numbers for particle i at time step t
+        let key = [42i64, 0i64];
+        let (i, t) = (17i64, 1000i64);
+        let mut prng = Threefry2x64::new_from_key(key, [0i64; 2]);
+        prng.next_long();
+        prng.set_counter([i, t]);
+        assert_eq!(prng.counter(), [i, t]);
+        let block = Threefry2x64::block(key, [i, t]);
+        assert_eq!(prng.next_long(), block[0]);
+        assert_eq!(prng.next_long(), block[1]);
+        // a partially consumed block gets skipped by next_block()
+        prng.set_counter([i, t]);
+        prng.next_long();
+        assert_eq!(prng.next_block(), Threefry2x64::block(key, [i + 1i64, t]));
+        assert_eq!(prng.next_block(), Threefry2x64::block(key, [i + 2i64, t]));
+    }
+
+    #[test]
+    fn test_advance() {
+        check_advance(Philox4x32::new_from(42i64));
+        check_advance(Philox4x64::new_from(42i64));
+        check_advance(Threefry2x64::new_from(42i64));
+    }
+}
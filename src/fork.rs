@@ -0,0 +1,110 @@
+// Copyright 2022 Stefan Zobel
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#[cfg(unix)]
+use crate::bit_mix::stafford_mix13;
+#[cfg(unix)]
+use crate::split_mix64_seed::GOLDEN;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
+#[cfg(unix)]
+use std::sync::Once;
+
+// incremented in the child process after every fork
+static FORK_GENERATION: AtomicU64 = AtomicU64::new(0u64);
+
+// the number of forks that this process has started
+#[cfg(unix)]
+static FORKS: AtomicU64 = AtomicU64::new(0u64);
+
+// identifies a forked process by the path of fork numbers that leads to it
+// from the original process, which has the key 0
+static FORK_KEY: AtomicI64 = AtomicI64::new(0i64);
+
+static RESEED_ON_FORK: AtomicBool = AtomicBool::new(true);
+
+#[cfg(unix)]
+static REGISTER_HANDLER: Once = Once::new();
+
+/// Enables or disables reseeding after a `fork()`. Reseeding is enabled by
+/// default: a child process that has been forked after the global seed state
+/// or a thread-local generator has been initialized reseeds them when it uses
+/// them the next time, so that parent and children don't produce the same
+/// random streams. Disable it if the child processes should continue with
+/// exactly the same streams as the parent. The setting applies to the forks
+/// that happen after the call.
+///
+/// Forks are detected on Unix systems only. Generators owned by the program
+/// are never reseeded, and in the deterministic mode of
+/// [set_global_seed()](crate::set_global_seed) the children reseed
+/// deterministically as well: the n-th fork of a process gets its own streams,
+/// counting from the first time a seed was requested in that process.
+///
+/// This function requires the `std` feature.
+pub fn set_reseed_on_fork(enabled: bool) {
+    RESEED_ON_FORK.store(enabled, Ordering::Relaxed);
+}
+
+#[cfg(unix)]
+extern "C" fn before_fork() {
+    FORKS.fetch_add(1u64, Ordering::Relaxed);
+}
+
+#[cfg(unix)]
+extern "C" fn after_fork_in_child() {
+    // only async-signal-safe operations are allowed here
+    if RESEED_ON_FORK.load(Ordering::Relaxed) {
+        FORK_GENERATION.fetch_add(1u64, Ordering::Relaxed);
+        // the child counts its own forks from scratch
+        let fork = FORKS.swap(0u64, Ordering::Relaxed) as i64;
+        let parent = FORK_KEY.load(Ordering::Relaxed);
+        let key = stafford_mix13(parent.wrapping_add(fork.wrapping_mul(GOLDEN)));
+        FORK_KEY.store(key, Ordering::Relaxed);
+    }
+}
+
+/// Installs the fork handler if that hasn't happened yet. Must be called
+/// before the state that depends on [fork_generation()] gets initialized.
+#[inline]
+pub(crate) fn register_fork_handler() {
+    #[cfg(unix)]
+    REGISTER_HANDLER.call_once(|| {
+        // SAFETY: the handlers are plain functions that only touch atomics
+        unsafe {
+            libc::pthread_atfork(Some(before_fork), None, Some(after_fork_in_child));
+        }
+    });
+}
+
+/// The number of forks that the current process has gone through with
+/// reseeding enabled (since the fork handler was registered).
+#[inline]
+pub(crate) fn fork_generation() -> u64 {
+    FORK_GENERATION.load(Ordering::Relaxed)
+}
+
+/// Distinguishes the processes that have been forked (with reseeding enabled)
+/// from each other and from the original process, which has the key 0. The
+/// key only depends on the order of the forks, so that it is reproducible.
+#[inline]
+pub(crate) fn fork_key() -> i64 {
+    FORK_KEY.load(Ordering::Relaxed)
+}
+
+#[cfg(all(test, unix))]
+mod fork_tests {
+    use super::*;
+
+    #[test]
+    fn test_register_fork_handler() {
+        register_fork_handler();
+        register_fork_handler();
+        assert!(REGISTER_HANDLER.is_completed());
+        // no fork has happened in the test process
+        assert_eq!(fork_generation(), 0u64);
+        assert_eq!(fork_key(), 0i64);
+    }
+}
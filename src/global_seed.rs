@@ -0,0 +1,212 @@
+// Copyright 2022 Stefan Zobel
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::bit_mix::{rrxmrrxmsx, stafford_mix13};
+use crate::fork::{fork_key, register_fork_handler};
+use crate::split_mix64_seed::GOLDEN;
+use parking_lot::lock_api::Mutex;
+use parking_lot::{const_mutex, RawMutex};
+use std::cell::Cell;
+
+const ENV_VAR: &str = "RND_SEED";
+
+struct GlobalSeed {
+    // true once the environment has been consulted
+    initialized: bool,
+    root: Option<i64>,
+    // incremented whenever a new root gets set, so that the per-thread
+    // counters start from scratch
+    generation: u64,
+    unnamed_threads: i64,
+    // the hash of each thread name that has been seen and the number of
+    // threads with that name
+    named_threads: Vec<(i64, i64)>,
+}
+
+static GLOBAL: Mutex<RawMutex, GlobalSeed> = const_mutex(GlobalSeed {
+    initialized: false,
+    root: None,
+    generation: 0u64,
+    unnamed_threads: 0i64,
+    named_threads: Vec::new(),
+});
+
+thread_local!(
+    // (generation, thread key, number of seeds handed out on this thread)
+    static THREAD_STREAM: Cell<(u64, i64, i64)> = Cell::new((0u64, 0i64, 0i64))
+);
+
+/// Switches the process into deterministic mode: from now on every `new()`
+/// constructor, every `Default` instance, every `from_entropy()` call and the
+/// generators behind [ThreadLocalPrng](crate::pseudo_random::ThreadLocalPrng)
+/// derive their seed from the given root `seed` instead of the system's
+/// entropy.
+///
+/// A seed is computed from the root, the current thread and the number of seeds
+/// that have already been requested on that thread. Threads with a name (the
+/// test harness names its threads after the test) are identified by their name
+/// and the number of threads with the same name that requested their first seed
+/// before them, unnamed threads by the order in which they request their first
+/// seed. The same program with the same root therefore sees exactly the same
+/// random streams, and the threads of a pool that share a name still get
+/// streams of their own.
+///
+/// Generators that have already been seeded keep their state. To make a whole
+/// run reproducible, call this function before any generator gets created or
+/// set the `RND_SEED` environment variable instead, which is read when the first
+/// seed is requested.
+///
+/// This function requires the `std` feature.
+pub fn set_global_seed(seed: u64) {
+    let mut global = GLOBAL.lock();
+    global.initialized = true;
+    global.root = Some(seed as i64);
+    global.generation += 1u64;
+    global.unnamed_threads = 0i64;
+    global.named_threads.clear();
+}
+
+/// Returns the root seed of the deterministic mode if it is enabled (either by
+/// [set_global_seed()] or by the `RND_SEED` environment variable), `None`
+/// otherwise. Print it when a randomized test fails and rerun the test with
+/// `RND_SEED` set to that value to get exactly the same randomness.
+///
+/// This function requires the `std` feature.
+pub fn global_seed() -> Option<u64> {
+    let mut global = GLOBAL.lock();
+    ensure_initialized(&mut global);
+    global.root.map(|root| root as u64)
+}
+
+fn ensure_initialized(global: &mut GlobalSeed) {
+    if !global.initialized {
+        global.initialized = true;
+        if let Ok(value) = std::env::var(ENV_VAR) {
+            global.root = Some(parse_seed(&value));
+            global.generation += 1u64;
+        }
+    }
+}
+
+// decimal or 0x-prefixed hexadecimal u64, any other value gets hashed
+fn parse_seed(value: &str) -> i64 {
+    let value = value.trim();
+    let hex = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"));
+    let parsed = match hex {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse::<u64>(),
+    };
+    match parsed {
+        Ok(seed) => seed as i64,
+        Err(_) => fnv1a(value.as_bytes()),
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> i64 {
+    let mut hash = 0xcbf29ce484222325u64;
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3u64);
+    }
+    hash as i64
+}
+
+fn thread_key(global: &mut GlobalSeed) -> i64 {
+    match std::thread::current().name() {
+        Some(name) => {
+            let hash = fnv1a(name.as_bytes());
+            let seen = match global.named_threads.iter_mut().find(|(h, _)| *h == hash) {
+                Some((_, count)) => {
+                    *count += 1i64;
+                    *count - 1i64
+                }
+                None => {
+                    global.named_threads.push((hash, 1i64));
+                    0i64
+                }
+            };
+            // the first thread with a given name is keyed by the name alone
+            hash.wrapping_add(stafford_mix13(seen))
+        }
+        None => {
+            global.unnamed_threads += 1i64;
+            stafford_mix13(global.unnamed_threads)
+        }
+    }
+}
+
+// the fork key separates the streams of forked child processes, which inherit
+// the thread key and the number of calls from their parent
+#[inline]
+fn derive_seed(root: i64, fork_key: i64, thread_key: i64, calls: i64) -> i64 {
+    let key = thread_key ^ fork_key;
+    let stream = stafford_mix13(root.wrapping_add(key.wrapping_mul(GOLDEN)));
+    rrxmrrxmsx(stream.wrapping_add(calls.wrapping_mul(GOLDEN)))
+}
+
+/// Returns the next seed of the current thread if the deterministic mode is
+/// enabled, `None` otherwise.
+pub(crate) fn deterministic_seed() -> Option<i64> {
+    let mut global = GLOBAL.lock();
+    ensure_initialized(&mut global);
+    let root = global.root?;
+    register_fork_handler();
+    let generation = global.generation;
+    let (key, calls) = THREAD_STREAM
+        .try_with(|stream| {
+            let (gen, mut key, mut calls) = stream.get();
+            if gen != generation {
+                key = thread_key(&mut global);
+                calls = 0i64;
+            }
+            calls += 1i64;
+            stream.set((generation, key, calls));
+            (key, calls)
+        })
+        // the thread-local counter has already been destroyed, this
+        // only happens in thread-local destructors
+        .unwrap_or_else(|_| {
+            global.unnamed_threads += 1i64;
+            (stafford_mix13(global.unnamed_threads), 0i64)
+        });
+    Some(derive_seed(root, fork_key(), key, calls))
+}
+
+#[cfg(test)]
+mod global_seed_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_seed() {
+        assert_eq!(parse_seed("42"), 42i64);
+        assert_eq!(parse_seed(" 42\n"), 42i64);
+        assert_eq!(parse_seed("0xff"), 255i64);
+        assert_eq!(parse_seed("0XFF"), 255i64);
+        assert_eq!(parse_seed("18446744073709551615"), -1i64);
+        assert_eq!(parse_seed("flaky"), fnv1a(b"flaky"));
+        assert_ne!(parse_seed("flaky"), parse_seed("flakY"));
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325u64 as i64);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8cu64 as i64);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8u64 as i64);
+    }
+
+    #[test]
+    fn test_derive_seed() {
+        let s1 = derive_seed(42i64, 0i64, 1i64, 1i64);
+        assert_eq!(s1, derive_seed(42i64, 0i64, 1i64, 1i64));
+        assert_ne!(s1, derive_seed(42i64, 0i64, 1i64, 2i64));
+        assert_ne!(s1, derive_seed(42i64, 0i64, 2i64, 1i64));
+        assert_ne!(s1, derive_seed(43i64, 0i64, 1i64, 1i64));
+        assert_ne!(s1, derive_seed(42i64, 7i64, 1i64, 1i64));
+    }
+}
@@ -0,0 +1,206 @@
+// Copyright 2022 Stefan Zobel
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//!
+//! Stateless random access to the values of a random stream.
+//!
+//! [random_at(seed, index)](random_at) returns the `index`-th value of the stream
+//! determined by `seed` in O(1) without any generator state, so that the values can
+//! be computed in any order or in parallel, e.g., for procedural generation or in a
+//! parallel map operation. The `_nd` variants take a multi-dimensional index like
+//! `[x, y, z]` or `[particle, time_step]`.
+//!
+//! The stream value is Pelle Evensen's [xNASAM](crate::bit_mix::xnasam) mixer applied to
+//! the `index`-th element of a Weyl sequence (increment 2<sup>64</sup>&nbsp;/&nbsp;&phi;)
+//! that starts at a point derived from `seed` by David Stafford's
+//! [Mix13](crate::bit_mix::stafford_mix13). For a fixed `seed` the
+//! map from `index` to value is a bijection. A multi-dimensional index is reduced
+//! by using the value for the leading indices as the seed for the next one.
+//!
+//! The values returned by these functions for a given seed and index are
+//! guaranteed to stay the same across releases of this crate.
+//!
+
+use crate::bit_mix::{stafford_mix13, xnasam};
+use crate::split_mix64_seed::GOLDEN;
+
+const DOUBLE_NORM: f64 = 1.0f64 / (1i64 << 53) as f64;
+
+/// Returns the uniformly distributed `index`-th value of the random stream
+/// determined by `seed`.
+#[inline]
+pub fn random_at(seed: u64, index: u64) -> u64 {
+    let start = stafford_mix13(seed as i64);
+    xnasam(start.wrapping_add((index as i64).wrapping_mul(GOLDEN))) as u64
+}
+
+/// Returns the `index`-th value of the random stream determined by `seed` as a
+/// uniformly distributed `f64` from the half-open interval [0.0, 1.0).
+#[inline]
+pub fn random_f64_at(seed: u64, index: u64) -> f64 {
+    to_unit_f64(random_at(seed, index))
+}
+
+/// Returns the `index`-th value of the random stream determined by `seed` as a
+/// uniformly distributed integer from the half-open interval [0, `bound`).
+///
+/// # Panics
+///
+/// Panics if `bound` is zero.
+#[inline]
+pub fn random_bounded_at(seed: u64, index: u64, bound: u64) -> u64 {
+    to_bounded(random_at(seed, index), bound)
+}
+
+/// Returns the uniformly distributed value at the multi-dimensional index
+/// `indices` of the random stream determined by `seed`. For a single index
+/// this is the same value as [random_at(seed, indices\[0\])](random_at).
+///
+/// # Panics
+///
+/// Panics if `indices` is empty.
+#[inline]
+pub fn random_at_nd(seed: u64, indices: &[u64]) -> u64 {
+    let (seed, index) = reduce(seed, indices);
+    random_at(seed, index)
+}
+
+/// Returns the value at the multi-dimensional index `indices` of the random
+/// stream determined by `seed` as a uniformly distributed `f64` from the
+/// half-open interval [0.0, 1.0).
+///
+/// # Panics
+///
+/// Panics if `indices` is empty.
+#[inline]
+pub fn random_f64_at_nd(seed: u64, indices: &[u64]) -> f64 {
+    to_unit_f64(random_at_nd(seed, indices))
+}
+
+/// Returns the value at the multi-dimensional index `indices` of the random
+/// stream determined by `seed` as a uniformly distributed integer from the
+/// half-open interval [0, `bound`).
+///
+/// # Panics
+///
+/// Panics if `indices` is empty or if `bound` is zero.
+#[inline]
+pub fn random_bounded_at_nd(seed: u64, indices: &[u64], bound: u64) -> u64 {
+    to_bounded(random_at_nd(seed, indices), bound)
+}
+
+// returns the seed for the last index and the last index
+#[inline]
+fn reduce(seed: u64, indices: &[u64]) -> (u64, u64) {
+    match indices.split_last() {
+        Some((&last, leading)) => (leading.iter().fold(seed, |s, &i| random_at(s, i)), last),
+        None => panic!("indices must not be empty"),
+    }
+}
+
+#[inline]
+fn to_unit_f64(x: u64) -> f64 {
+    (x >> 11) as f64 * DOUBLE_NORM
+}
+
+// Lemire's (2019) multiply-shift method, a rejected candidate gets replaced
+// by the Mix13 of the candidate plus the golden gamma
+#[inline]
+fn to_bounded(mut x: u64, bound: u64) -> u64 {
+    if bound == 0u64 {
+        panic!("bound must be strictly positive");
+    }
+    let mut m = (x as u128) * (bound as u128);
+    if (m as u64) < bound {
+        let threshold = bound.wrapping_neg() % bound;
+        while (m as u64) < threshold {
+            x = stafford_mix13((x as i64).wrapping_add(GOLDEN)) as u64;
+            m = (x as u128) * (bound as u128);
+        }
+    }
+    (m >> 64) as u64
+}
+
+#[cfg(test)]
+mod hash_tests {
+    use super::*;
+
+    #[test]
+    fn test_random_at() {
+        // these values must never change
+        assert_eq!(random_at(0u64, 0u64), 0x30cf880dfe91b0a6u64);
+        assert_eq!(random_at(0u64, 1u64), 0x9351ecb2293669a2u64);
+        assert_eq!(random_at(42u64, 1000u64), 0x4ca9f1172ad05116u64);
+        assert_ne!(random_at(1u64, 0u64), random_at(0u64, 0u64));
+        assert_ne!(random_at(0u64, 2u64), random_at(0u64, 1u64));
+    }
+
+    #[test]
+    fn test_random_f64_at() {
+        let mut sum = 0.0f64;
+        for i in 0..10_000u64 {
+            let d = random_f64_at(7u64, i);
+            assert!((0.0f64..1.0f64).contains(&d));
+            sum += d;
+        }
+        assert!((sum / 10_000.0f64 - 0.5f64).abs() < 0.01f64);
+        assert_eq!(
+            random_f64_at(0u64, 0u64),
+            (0x30cf880dfe91b0a6u64 >> 11) as f64 * DOUBLE_NORM
+        );
+    }
+
+    #[test]
+    fn test_random_bounded_at() {
+        let mut counts = [0u32; 6];
+        for i in 0..60_000u64 {
+            counts[random_bounded_at(3u64, i, 6u64) as usize] += 1u32;
+        }
+        for &c in &counts {
+            assert!((9_500u32..10_500u32).contains(&c));
+        }
+        assert_eq!(random_bounded_at(3u64, 5u64, 1u64), 0u64);
+        for i in 0..1000u64 {
+            // the largest bound rejects almost half of the candidates
+            let bound = (1u64 << 63) + 1u64;
+            assert!(random_bounded_at(5u64, i, bound) < bound);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "bound must be strictly positive")]
+    fn test_random_bounded_at_zero_bound() {
+        random_bounded_at(1u64, 2u64, 0u64);
+    }
+
+    #[test]
+    fn test_random_at_nd() {
+        assert_eq!(random_at_nd(9u64, &[4u64]), random_at(9u64, 4u64));
+        assert_eq!(
+            random_at_nd(9u64, &[4u64, 5u64, 6u64]),
+            random_at(random_at(random_at(9u64, 4u64), 5u64), 6u64)
+        );
+        assert_ne!(
+            random_at_nd(9u64, &[4u64, 5u64]),
+            random_at_nd(9u64, &[5u64, 4u64])
+        );
+        assert_eq!(
+            random_f64_at_nd(9u64, &[4u64, 5u64]),
+            random_f64_at(random_at(9u64, 4u64), 5u64)
+        );
+        assert_eq!(
+            random_bounded_at_nd(9u64, &[4u64, 5u64], 100u64),
+            random_bounded_at(random_at(9u64, 4u64), 5u64, 100u64)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "indices must not be empty")]
+    fn test_random_at_nd_empty() {
+        random_at_nd(1u64, &[]);
+    }
+}
@@ -0,0 +1,495 @@
+// Copyright 2022 Stefan Zobel
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Generators that reproduce the streams of the JDK's `java.util.Random` and
+//! `java.util.SplittableRandom` bit for bit.
+
+use crate::bit_mix::{murmur_mix64, stafford_mix04, stafford_mix13};
+use crate::pseudo_random::{PseudoRandom, Splittable};
+#[cfg(feature = "std")]
+use crate::split_mix64_seed::seed;
+use crate::split_mix64_seed::GOLDEN;
+
+const MULTIPLIER: i64 = 0x5deece66di64;
+const ADDEND: i64 = 0xbi64;
+const MASK: i64 = (1i64 << 48) - 1i64;
+
+const DOUBLE_NORM: f64 = 1.0f64 / (1i64 << 53) as f64;
+const FLOAT_NORM: f32 = 1.0f32 / (1i32 << 24) as f32;
+
+/// The 48-bit linear congruential generator of `java.util.Random`. For the
+/// same seed it produces exactly the same values as the Java class does,
+/// including the bounded integers of `nextInt(bound)`, the bytes of
+/// `nextBytes()` and the (cached) values of `nextGaussian()`.
+///
+/// This generator has a period of 2<sup>48</sup> and its low-order bits
+/// are of poor quality. It should only be used where compatibility with
+/// existing Java code is required.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JavaRandom {
+    // the 48-bit LCG state
+    seed: i64,
+    // the second value of the last pair produced by next_gaussian()
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    next_next_gaussian: f64,
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    have_next_next_gaussian: bool,
+}
+
+impl PseudoRandom for JavaRandom {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        let hi = (self.next_bits(32) as i64) << 32;
+        hi.wrapping_add(self.next_bits(32) as i64)
+    }
+
+    #[inline]
+    fn next_int(&mut self) -> i32 {
+        self.next_bits(32)
+    }
+
+    #[inline]
+    fn next_double(&mut self) -> f64 {
+        let hi = (self.next_bits(26) as i64) << 27;
+        (hi + self.next_bits(27) as i64) as f64 * DOUBLE_NORM
+    }
+
+    #[inline]
+    fn next_float(&mut self) -> f32 {
+        self.next_bits(24) as f32 * FLOAT_NORM
+    }
+
+    #[inline]
+    fn next_bool(&mut self) -> bool {
+        self.next_bits(1) != 0i32
+    }
+
+    /// Returns the same value as `java.util.Random.nextInt(n)` does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero or negative.
+    #[inline]
+    fn next_int_up_to(&mut self, n: i32) -> i32 {
+        if n <= 0i32 {
+            panic!("n must be strictly positive");
+        }
+        let mut r = self.next_bits(31);
+        let m = n - 1i32;
+        if (n & m) == 0i32 {
+            // power of two shortcut
+            return ((n as i64 * r as i64) >> 31) as i32;
+        }
+        // reject candidates from the incomplete last interval of length n
+        let mut u = r;
+        loop {
+            r = u % n;
+            if u.wrapping_sub(r).wrapping_add(m) >= 0i32 {
+                return r;
+            }
+            u = self.next_bits(31);
+        }
+    }
+
+    /// Fills `bytes` in the same way as `java.util.Random.nextBytes()` does,
+    /// i.e., from consecutive 32-bit values.
+    #[inline]
+    fn next_bytes(&mut self, bytes: &mut [u8]) {
+        for chunk in bytes.chunks_mut(4) {
+            let rnd = self.next_int();
+            let len = chunk.len();
+            chunk.copy_from_slice(&rnd.to_le_bytes()[..len]);
+        }
+    }
+
+    /// Returns the same pair of values as two calls of
+    /// `java.util.Random.nextGaussian()` do. Unlike the default implementation
+    /// this uses the logarithm of Java's `StrictMath`, so that the values don't
+    /// depend on the platform's math library.
+    ///
+    /// This method requires the `std` feature.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn next_gaussians(&mut self, out: &mut [f64; 2]) {
+        let mut u1: f64;
+        let mut u2: f64;
+        let mut q: f64;
+        loop {
+            u1 = 2.0f64 * self.next_double() - 1.0f64;
+            u2 = 2.0f64 * self.next_double() - 1.0f64;
+            q = u1 * u1 + u2 * u2;
+            if q < 1.0f64 && q != 0.0f64 {
+                break;
+            }
+        }
+        // sqrt() is correctly rounded on every platform
+        let p = (-2.0f64 * strict_log(q) / q).sqrt();
+        out[0] = u1 * p;
+        out[1] = u2 * p;
+    }
+}
+
+// The natural logarithm of fdlibm's __ieee754_log (e_log.c), which is what
+// Java's StrictMath.log() computes. The constants are given by their bits.
+#[cfg(feature = "std")]
+fn strict_log(mut x: f64) -> f64 {
+    const LN2_HI: u64 = 0x3fe62e42fee00000u64;
+    const LN2_LO: u64 = 0x3dea39ef35793c76u64;
+    const TWO54: u64 = 0x4350000000000000u64;
+    const ONE_THIRD: u64 = 0x3fd5555555555555u64;
+    const LG: [u64; 7] = [
+        0x3fe5555555555593u64,
+        0x3fd999999997fa04u64,
+        0x3fd2492494229359u64,
+        0x3fcc71c51d8e78afu64,
+        0x3fc7466496cb03deu64,
+        0x3fc39a09d078c69fu64,
+        0x3fc2f112df3e5244u64,
+    ];
+    let ln2_hi = f64::from_bits(LN2_HI);
+    let ln2_lo = f64::from_bits(LN2_LO);
+    let [lg1, lg2, lg3, lg4, lg5, lg6, lg7] = LG.map(f64::from_bits);
+    let mut hx = (x.to_bits() >> 32) as i32;
+    let lx = x.to_bits() as u32;
+    let mut k = 0i32;
+    if hx < 0x00100000i32 {
+        // x < 2^-1022
+        if ((hx & 0x7fffffffi32) as u32 | lx) == 0u32 {
+            return f64::NEG_INFINITY;
+        }
+        if hx < 0i32 {
+            return f64::NAN;
+        }
+        // subnormal, scale up x
+        k -= 54i32;
+        x *= f64::from_bits(TWO54);
+        hx = (x.to_bits() >> 32) as i32;
+    }
+    if hx >= 0x7ff00000i32 {
+        return x + x;
+    }
+    k += (hx >> 20) - 1023i32;
+    hx &= 0x000fffffi32;
+    let i = (hx + 0x95f64i32) & 0x100000i32;
+    // normalize x or x / 2
+    let high = (hx | (i ^ 0x3ff00000i32)) as u32 as u64;
+    x = f64::from_bits((high << 32) | (x.to_bits() & 0xffffffffu64));
+    k += i >> 20;
+    let f = x - 1.0f64;
+    let dk = k as f64;
+    if (0x000fffffi32 & (2i32 + hx)) < 3i32 {
+        // |f| < 2^-20
+        if f == 0.0f64 {
+            return dk * ln2_hi + dk * ln2_lo;
+        }
+        let r = f * f * (0.5f64 - f64::from_bits(ONE_THIRD) * f);
+        return if k == 0i32 {
+            f - r
+        } else {
+            dk * ln2_hi - ((r - dk * ln2_lo) - f)
+        };
+    }
+    let s = f / (2.0f64 + f);
+    let z = s * s;
+    let w = z * z;
+    let t1 = w * (lg2 + w * (lg4 + w * lg6));
+    let t2 = z * (lg1 + w * (lg3 + w * (lg5 + w * lg7)));
+    let r = t2 + t1;
+    if ((hx - 0x6147ai32) | (0x6b851i32 - hx)) > 0i32 {
+        let hfsq = 0.5f64 * f * f;
+        if k == 0i32 {
+            f - (hfsq - s * (hfsq + r))
+        } else {
+            dk * ln2_hi - ((hfsq - (s * (hfsq + r) + dk * ln2_lo)) - f)
+        }
+    } else if k == 0i32 {
+        f - s * (f - r)
+    } else {
+        dk * ln2_hi - ((s * (f - r) - dk * ln2_lo) - f)
+    }
+}
+
+impl JavaRandom {
+    /// Creates a new [JavaRandom](JavaRandom) initialized with a random seed.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn new() -> Self {
+        JavaRandom::new_from(seed())
+    }
+
+    /// Creates a new [JavaRandom](JavaRandom) initialized with the given `seed`
+    /// in the same way as by the `java.util.Random(long seed)` constructor.
+    #[inline]
+    pub fn new_from(seed: i64) -> Self {
+        JavaRandom {
+            seed: (seed ^ MULTIPLIER) & MASK,
+            next_next_gaussian: 0.0f64,
+            have_next_next_gaussian: false,
+        }
+    }
+
+    /// Resets this generator to the state of a new generator created by
+    /// [new_from(seed)](Self::new_from), as `java.util.Random.setSeed()` does.
+    #[inline]
+    pub fn set_seed(&mut self, seed: i64) {
+        *self = JavaRandom::new_from(seed);
+    }
+
+    /// Returns a standard normal distributed value in the same way as
+    /// `java.util.Random.nextGaussian()` does. Each call of the polar method
+    /// produces two values, the second one is returned by the next call. The
+    /// logarithm is a port of the one of Java's `StrictMath`, so that the
+    /// values are the same on every platform.
+    ///
+    /// This method requires the `std` feature.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn next_gaussian(&mut self) -> f64 {
+        if self.have_next_next_gaussian {
+            self.have_next_next_gaussian = false;
+            return self.next_next_gaussian;
+        }
+        let mut out = [0.0f64; 2];
+        self.next_gaussians(&mut out);
+        self.next_next_gaussian = out[1];
+        self.have_next_next_gaussian = true;
+        out[0]
+    }
+
+    // the equivalent of java.util.Random.next(int bits)
+    #[inline]
+    fn next_bits(&mut self, bits: u32) -> i32 {
+        self.seed = self.seed.wrapping_mul(MULTIPLIER).wrapping_add(ADDEND) & MASK;
+        (self.seed >> (48 - bits)) as i32
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for JavaRandom {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The SplitMix64 algorithm of `java.util.SplittableRandom` by Guy Steele,
+/// Doug Lea and Christine Flood (2014). For the same seed it produces exactly
+/// the same values as the Java class does.
+///
+/// This generator has a period of 2<sup>64</sup>.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JavaSplittableRandom {
+    state: i64,
+    // Weyl generator step value, must be odd
+    gamma: i64,
+}
+
+impl PseudoRandom for JavaSplittableRandom {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        stafford_mix13(self.next_seed())
+    }
+
+    #[inline]
+    fn next_int(&mut self) -> i32 {
+        stafford_mix04(self.next_seed())
+    }
+
+    #[inline]
+    fn next_float(&mut self) -> f32 {
+        (self.next_int() as u32 >> 8) as f32 * FLOAT_NORM
+    }
+
+    #[inline]
+    fn next_bool(&mut self) -> bool {
+        self.next_int() < 0i32
+    }
+}
+
+impl JavaSplittableRandom {
+    /// Creates a new [JavaSplittableRandom](JavaSplittableRandom) initialized with a random seed.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn new() -> Self {
+        JavaSplittableRandom::new_from(seed())
+    }
+
+    /// Creates a new [JavaSplittableRandom](JavaSplittableRandom) initialized with the
+    /// given `seed` in the same way as by the `java.util.SplittableRandom(long seed)`
+    /// constructor.
+    #[inline]
+    pub fn new_from(seed: i64) -> Self {
+        JavaSplittableRandom {
+            state: seed,
+            gamma: GOLDEN,
+        }
+    }
+
+    #[inline]
+    fn next_seed(&mut self) -> i64 {
+        self.state = self.state.wrapping_add(self.gamma);
+        self.state
+    }
+}
+
+impl Splittable for JavaSplittableRandom {
+    /// Returns a new [JavaSplittableRandom](JavaSplittableRandom) in the same way
+    /// as `java.util.SplittableRandom.split()` does.
+    #[inline]
+    fn split(&mut self) -> Self {
+        let state = self.next_long();
+        JavaSplittableRandom {
+            state,
+            gamma: mix_gamma(self.next_seed()),
+        }
+    }
+}
+
+// the gamma mixing function of java.util.SplittableRandom
+#[inline]
+const fn mix_gamma(v: i64) -> i64 {
+    // force v to be odd
+    let v = murmur_mix64(v) | 1i64;
+    // try to support enough 01 and 10 transitions
+    let n = (v ^ (v as u64 >> 1) as i64).count_ones();
+    if n < 24 {
+        v ^ 0xaaaaaaaaaaaaaaaau64 as i64
+    } else {
+        v
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for JavaSplittableRandom {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod java_tests {
+    use super::*;
+
+    #[test]
+    fn test_java_random() {
+        // reference values from java.util.Random(42)
+        let mut prng = JavaRandom::new_from(42i64);
+        assert_eq!(prng.next_int(), -1170105035i32);
+        assert_eq!(prng.next_int(), 234785527i32);
+        assert_eq!(prng.next_long(), -5843495416241995736i64);
+        assert_eq!(prng.next_double(), 0.30871945533265976f64);
+        assert_eq!(prng.next_float(), 0.27707845f32);
+        assert!(prng.next_bool());
+        assert!(prng.next_bool());
+        // reference values from java.util.Random(-7)
+        let mut prng = JavaRandom::new_from(-7i64);
+        assert_eq!(prng.next_int(), 1155869324i32);
+        assert_eq!(prng.next_long(), -1817049056290694612i64);
+        assert_eq!(prng.next_double(), 0.9458454776032215f64);
+    }
+
+    #[test]
+    fn test_java_random_bounded() {
+        // reference values from java.util.Random(42)
+        let mut prng = JavaRandom::new_from(42i64);
+        assert_eq!(prng.next_int_up_to(10i32), 0i32);
+        assert_eq!(prng.next_int_up_to(10i32), 3i32);
+        assert_eq!(prng.next_int_up_to(1i32 << 20), 716411i32);
+        assert_eq!(prng.next_int_up_to(1000000007i32), 102948884i32);
+        assert_eq!(prng.next_int_up_to(i32::MAX), 662969970i32);
+        // reference values from java.util.Random(1), about half of the
+        // candidates get rejected for this bound
+        let mut prng = JavaRandom::new_from(1i64);
+        let expected = [215764588i32, 880641847i32, 874970313i32, 446064254i32];
+        for &i in &expected {
+            assert_eq!(prng.next_int_up_to((1i32 << 30) + 1i32), i);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_java_random_gaussian() {
+        // reference values from java.util.Random(42)
+        let mut prng = JavaRandom::new_from(42i64);
+        for _ in 0..5 {
+            prng.next_int_up_to(10i32);
+        }
+        assert_eq!(prng.next_gaussian(), 0.27686040089698144f64);
+        assert_eq!(prng.next_gaussian(), 0.13008432156952723f64);
+        assert_eq!(prng.next_gaussian(), -0.8761154839986063f64);
+        // the cached value of the second pair is still pending
+        assert_eq!(prng.next_int(), 1639144584i32);
+        let mut bytes = [0u8; 7];
+        prng.next_bytes(&mut bytes);
+        assert_eq!(bytes, [0xf8u8, 108u8, 0x97u8, 70u8, 32u8, 0xbeu8, 0xbfu8]);
+        // set_seed() discards the cached value
+        prng.set_seed(-3i64);
+        prng.next_gaussian();
+        prng.set_seed(-3i64);
+        assert_eq!(prng.next_gaussian(), -1.3012597153498027f64);
+        assert_eq!(prng.next_gaussian(), -0.8364632103720995f64);
+        // the 13th value of java.util.Random(0) differs in the last bit if
+        // the logarithm of glibc's libm is used
+        let mut prng = JavaRandom::new_from(0i64);
+        for _ in 0..11 {
+            prng.next_gaussian();
+        }
+        assert_eq!(prng.next_gaussian(), 0.5213420769298895f64);
+        assert_eq!(prng.next_gaussian(), -0.8239670026881707f64);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_strict_log() {
+        // reference values from Java's StrictMath.log()
+        assert_eq!(strict_log(0.6949479796024919f64), -0.3639182857115254f64);
+        assert_eq!(strict_log(0.005025175992452557f64), -5.293294802276659f64);
+        assert_eq!(strict_log(0.16104332337170224f64), -1.8260818609304654f64);
+        assert_eq!(strict_log(1.0000001f64), 9.999999505838704e-8f64);
+        assert_eq!(
+            strict_log(f64::MIN_POSITIVE / 2.0f64),
+            -709.0895657128241f64
+        );
+        assert_eq!(strict_log(1e-310f64), -713.8013788281542f64);
+        assert_eq!(strict_log(f64::MAX), 709.782712893384f64);
+        assert_eq!(strict_log(1.0f64), 0.0f64);
+        assert_eq!(strict_log(0.0f64), f64::NEG_INFINITY);
+        assert!(strict_log(-1.0f64).is_nan());
+    }
+
+    #[test]
+    fn test_java_splittable_random() {
+        // reference values from java.util.SplittableRandom(42)
+        let mut prng = JavaSplittableRandom::new_from(42i64);
+        assert_eq!(prng.next_long(), -4767286540954276203i64);
+        assert_eq!(prng.next_long(), 2949826092126892291i64);
+        assert_eq!(prng.next_int(), -1877322334i32);
+        assert_eq!(prng.next_int(), -1024560952i32);
+        assert_eq!(prng.next_float(), 0.6412094f32);
+        assert!(prng.next_bool());
+        assert!(!prng.next_bool());
+        assert_eq!(prng.next_double(), 0.8006318767135033f64);
+    }
+
+    #[test]
+    fn test_java_splittable_random_split() {
+        // reference values from java.util.SplittableRandom(42)
+        let mut prng = JavaSplittableRandom::new_from(42i64);
+        prng.next_long();
+        let mut child = prng.split();
+        assert_eq!(child.next_long(), -3551978058637500346i64);
+        assert_eq!(child.next_long(), 2599073511381211903i64);
+        assert_eq!(prng.next_long(), 6349198060258255764i64);
+        assert_eq!(prng.next_long(), 701532786141963250i64);
+        let mut grandchild = child.split();
+        assert_eq!(grandchild.next_long(), -3695472819293523185i64);
+        assert_eq!(grandchild.next_int(), -1126607892i32);
+    }
+}
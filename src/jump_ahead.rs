@@ -0,0 +1,164 @@
+// Copyright 2022 Stefan Zobel
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+/*
+ * Jumping ahead in F2-linear generators (xorshift, xoshiro, xoroshiro) works
+ * by computing the jump polynomial x^n mod p(x) over GF(2) where p(x) is the
+ * characteristic polynomial of the generator's state transition matrix T.
+ * Since p(T) = 0 we get T^n = c(T) for c(x) = x^n mod p(x) and the state
+ * after n steps is the sum (xor) of those states T^i(s) for which the
+ * coefficient c_i is 1. See Haramoto et al. (2008), "Efficient Jump Ahead
+ * for F2-Linear Random Number Generators".
+ *
+ * All characteristic polynomials used in this crate are monic and have a
+ * degree of 64 * N. They are represented by their N lower words where bit b
+ * of word i is the coefficient of x^(64i + b).
+ */
+
+#[inline]
+fn mul_x<const N: usize>(v: &mut [u64; N], p: &[u64; N]) {
+    let carry = v[N - 1] >> 63;
+    for i in (1..N).rev() {
+        v[i] = (v[i] << 1) | (v[i - 1] >> 63);
+    }
+    v[0] <<= 1;
+    if carry != 0u64 {
+        for i in 0..N {
+            v[i] ^= p[i];
+        }
+    }
+}
+
+fn mul_mod<const N: usize>(a: &[u64; N], b: &[u64; N], p: &[u64; N]) -> [u64; N] {
+    let mut r = [0u64; N];
+    for i in (0..N).rev() {
+        for bit in (0..64).rev() {
+            mul_x(&mut r, p);
+            if (b[i] >> bit) & 1u64 != 0u64 {
+                for j in 0..N {
+                    r[j] ^= a[j];
+                }
+            }
+        }
+    }
+    r
+}
+
+/// Returns the coefficients of x<sup>n</sup> mod p(x) over GF(2) where
+/// p(x) is the monic polynomial of degree 64&nbsp;*&nbsp;N whose lower
+/// coefficients are given by `p`.
+pub(crate) fn x_pow_mod<const N: usize>(n: u128, p: &[u64; N]) -> [u64; N] {
+    let mut r = [0u64; N];
+    r[0] = 1u64;
+    if n == 0u128 {
+        return r;
+    }
+    for bit in (0..(128 - n.leading_zeros())).rev() {
+        r = mul_mod(&r, &r, p);
+        if (n >> bit) & 1u128 != 0u128 {
+            mul_x(&mut r, p);
+        }
+    }
+    r
+}
+
+/// Returns the state of the 64-bit LCG `s = m * s + a` after `n` steps.
+/// This is Brown's (1994) algorithm which needs O(log n) multiplications.
+pub(crate) fn lcg_advance(s: i64, m: i64, a: i64, mut n: u128) -> i64 {
+    let mut acc_mult = 1i64;
+    let mut acc_plus = 0i64;
+    let mut cur_mult = m;
+    let mut cur_plus = a;
+    while n > 0u128 {
+        if n & 1u128 != 0u128 {
+            acc_mult = acc_mult.wrapping_mul(cur_mult);
+            acc_plus = acc_plus.wrapping_mul(cur_mult).wrapping_add(cur_plus);
+        }
+        cur_plus = cur_mult.wrapping_add(1i64).wrapping_mul(cur_plus);
+        cur_mult = cur_mult.wrapping_mul(cur_mult);
+        n >>= 1;
+    }
+    acc_mult.wrapping_mul(s).wrapping_add(acc_plus)
+}
+
+/// Returns the state of the 128-bit LCG `s = m * s + a` after `n` steps
+/// (see [lcg_advance](lcg_advance)).
+pub(crate) fn lcg_advance128(s: i128, m: i128, a: i128, mut n: u128) -> i128 {
+    let mut acc_mult = 1i128;
+    let mut acc_plus = 0i128;
+    let mut cur_mult = m;
+    let mut cur_plus = a;
+    while n > 0u128 {
+        if n & 1u128 != 0u128 {
+            acc_mult = acc_mult.wrapping_mul(cur_mult);
+            acc_plus = acc_plus.wrapping_mul(cur_mult).wrapping_add(cur_plus);
+        }
+        cur_plus = cur_mult.wrapping_add(1i128).wrapping_mul(cur_plus);
+        cur_mult = cur_mult.wrapping_mul(cur_mult);
+        n >>= 1;
+    }
+    acc_mult.wrapping_mul(s).wrapping_add(acc_plus)
+}
+
+#[cfg(test)]
+mod jump_ahead_tests {
+    use super::*;
+
+    #[test]
+    fn test_x_pow_mod() {
+        // the characteristic polynomial of xoshiro256 must reproduce
+        // Blackman and Vigna's published jump polynomial for 2^128 steps
+        let p = [
+            0x9d116f2bb0f0f001u64,
+            0x0280002bcefd1a5eu64,
+            0x04b4edcf26259f85u64,
+            0x0003c03c3f3ecb19u64,
+        ];
+        let half = x_pow_mod(1u128 << 127, &p);
+        let jump = mul_mod(&half, &half, &p);
+        assert_eq!(
+            jump,
+            [
+                0x180ec6d33cfd0abau64,
+                0xd5a61266f0c9392cu64,
+                0xa9582618e03fc9aau64,
+                0x39abdc4529b1661cu64,
+            ]
+        );
+        assert_eq!(x_pow_mod(0u128, &p), [1u64, 0u64, 0u64, 0u64]);
+        assert_eq!(x_pow_mod(67u128, &p), [0u64, 8u64, 0u64, 0u64]);
+    }
+
+    #[test]
+    fn test_lcg_advance() {
+        let m = 0xd1342543de82ef95u64 as i64;
+        let a = 0x1234567i64;
+        let mut s = 42i64;
+        for _ in 0..1000 {
+            s = m.wrapping_mul(s).wrapping_add(a);
+        }
+        assert_eq!(lcg_advance(42i64, m, a, 1000u128), s);
+        assert_eq!(lcg_advance(42i64, m, a, 0u128), 42i64);
+        // the LCG has a period of 2^64
+        assert_eq!(lcg_advance(42i64, m, a, 1u128 << 64), 42i64);
+    }
+
+    #[test]
+    fn test_lcg_advance128() {
+        let m = 0x2360ed051fc65da44385df649fccf645u128 as i128;
+        let a = 0x1234567i128;
+        let mut s = 42i128;
+        for _ in 0..1000 {
+            s = m.wrapping_mul(s).wrapping_add(a);
+        }
+        assert_eq!(lcg_advance128(42i128, m, a, 1000u128), s);
+        assert_eq!(lcg_advance128(42i128, m, a, 0u128), 42i128);
+        // going around the period of 2^128 once more ends up at the start
+        let t = lcg_advance128(42i128, m, a, u128::MAX);
+        assert_eq!(m.wrapping_mul(t).wrapping_add(a), 42i128);
+    }
+}
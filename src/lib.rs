@@ -9,24 +9,71 @@
 //! number generators ported from Java.
 
 #![crate_name="rnd"]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![warn(missing_docs)]
 #![warn(rust_2018_idioms)]
 
-mod bit_mix;
+extern crate alloc;
+
+pub mod bit_mix;
+mod counter_based;
+#[cfg(feature = "std")]
+mod fork;
+#[cfg(feature = "std")]
+pub use crate::fork::set_reseed_on_fork;
+#[cfg(feature = "std")]
+mod global_seed;
+#[cfg(feature = "std")]
+pub use crate::global_seed::{global_seed, set_global_seed};
+pub mod hash;
+mod java;
+mod jump_ahead;
+mod lxm;
+mod pcg;
 pub mod pseudo_random;
+#[cfg(feature = "rand_core")]
+mod rand_compat;
+#[cfg(feature = "std")]
 mod seed;
+#[cfg(feature = "std")]
+pub use crate::seed::{entropy_seed, try_entropy_seed, EntropyError};
 mod split_mix64;
 mod split_mix64_seed;
+mod state_format;
+#[cfg(test)]
+mod test_util;
+#[cfg(feature = "std")]
+mod thread_local_prng;
 mod xor_shift_128plus;
+mod xoshiro;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod lib_tests {
+    use crate::pseudo_random::Advance;
+    use crate::pseudo_random::IllegalStateError;
+    use crate::pseudo_random::JavaSplittableRandom;
     use crate::pseudo_random::Lcg64Xor1024Mix;
-    use crate::pseudo_random::PseudoRandom as _;
+    use crate::pseudo_random::Pcg32;
+    use crate::pseudo_random::Pcg64;
+    use crate::pseudo_random::Pcg64Dxsm;
+    use crate::pseudo_random::Philox4x32;
+    use crate::pseudo_random::Philox4x64;
+    use crate::pseudo_random::PseudoRandom;
+    use crate::pseudo_random::Seedable;
+    use crate::pseudo_random::Splittable;
+    use crate::pseudo_random::StateFormatError;
     use crate::pseudo_random::Stc64;
+    use crate::pseudo_random::Threefry2x64;
     use crate::pseudo_random::XoShiRo256StarStar;
+    use crate::pseudo_random::Xoroshiro1024StarStar;
+    use crate::pseudo_random::Xoroshiro128PlusPlus;
+    use crate::pseudo_random::Xoroshiro128StarStar;
+    use crate::pseudo_random::Xoshiro256Plus;
+    use crate::pseudo_random::Xoshiro256PlusPlus;
+    use crate::pseudo_random::Xoshiro512StarStar;
     use crate::seed::raw_seed;
     use crate::split_mix64::SplitMix64;
+    use crate::test_util::check_advance;
     use crate::xor_shift_128plus::XorShift128Plus;
 
     #[test]
@@ -104,6 +151,21 @@ mod lib_tests {
         assert_ne!(l, i as i64);
     }
 
+    #[test]
+    fn test_split_mix64_from_state() {
+        let mut rng1 = SplitMix64::new_from_state(42i64, 3i64).unwrap();
+        let mut rng2 = rng1.clone();
+        // the child starts at the parent's next output but doesn't
+        // inherit the parent's gamma
+        let mut same_gamma = SplitMix64::new_from_state(rng2.next_long(), 3i64).unwrap();
+        let mut child = rng1.split();
+        assert_ne!(child.next_long(), same_gamma.next_long());
+        assert_eq!(
+            SplitMix64::new_from_state(42i64, 2i64).unwrap_err(),
+            IllegalStateError::EvenIncrement
+        );
+    }
+
     #[test]
     fn test_xor_shift_128plus() {
         let mut rng1 = XorShift128Plus::new_from(123i64);
@@ -118,4 +180,375 @@ mod lib_tests {
         }
         assert_ne!(rng1.next_long(), rng2.next_long());
     }
+
+    #[test]
+    fn test_xoshiro_from_state() {
+        let mut prng = XoShiRo256StarStar::new_from_state([1i64, 2i64, 3i64, 4i64]).unwrap();
+        // reference values from Blackman and Vigna's C implementation
+        assert_eq!(prng.next_long(), 11520i64);
+        assert_eq!(prng.next_long(), 0i64);
+        assert_eq!(prng.next_long(), 1509978240i64);
+        assert_eq!(prng.next_long(), 1215971899390074240i64);
+        assert_eq!(
+            XoShiRo256StarStar::new_from_state([0i64; 4]).unwrap_err(),
+            IllegalStateError::AllZero
+        );
+    }
+
+    #[test]
+    fn test_lcg64_xor_1024mix_from_state() {
+        let mut seed = [0i64; 16];
+        for (i, x) in seed.iter_mut().enumerate() {
+            *x = (i as i64 + 1i64).wrapping_mul(0x9e3779b97f4a7c15u64 as i64);
+        }
+        let mut prng = Lcg64Xor1024Mix::new_from_state(3i64, 0x1234567i64, seed).unwrap();
+        // reference values from JDK 17's L64X1024MixRandom
+        assert_eq!(prng.next_long(), -2714861467225706635i64);
+        assert_eq!(prng.next_long(), 2842819964677328091i64);
+        assert_eq!(prng.next_long(), 5288475746724432055i64);
+        assert_eq!(prng.next_long(), 5237838030699927133i64);
+        assert_eq!(prng.next_long(), -4046439477872202629i64);
+        assert_eq!(
+            Lcg64Xor1024Mix::new_from_state(2i64, 0i64, seed).unwrap_err(),
+            IllegalStateError::EvenIncrement
+        );
+        assert_eq!(
+            Lcg64Xor1024Mix::new_from_state(1i64, 0i64, [0i64; 16]).unwrap_err(),
+            IllegalStateError::AllZero
+        );
+    }
+
+    fn check_seedable<G: PseudoRandom + Seedable>() {
+        let mut prng1 = G::seed_from_u64(42u64);
+        let mut prng2 = G::seed_from_u64(42u64);
+        let mut prng3 = G::seed_from_u64(43u64);
+        let mut prng4 = G::from_entropy();
+        for _ in 0..16 {
+            let l = prng1.next_long();
+            assert_eq!(l, prng2.next_long());
+            assert_ne!(l, prng3.next_long());
+            assert_ne!(l, prng4.next_long());
+        }
+    }
+
+    #[test]
+    fn test_seedable() {
+        check_seedable::<Stc64>();
+        check_seedable::<XoShiRo256StarStar>();
+        check_seedable::<Lcg64Xor1024Mix>();
+        check_seedable::<SplitMix64>();
+        check_seedable::<Xoshiro256PlusPlus>();
+        check_seedable::<Xoshiro256Plus>();
+        check_seedable::<Xoroshiro128PlusPlus>();
+        check_seedable::<Xoroshiro128StarStar>();
+        check_seedable::<Xoshiro512StarStar>();
+        check_seedable::<Xoroshiro1024StarStar>();
+        check_seedable::<Pcg32>();
+        check_seedable::<Pcg64>();
+        check_seedable::<Pcg64Dxsm>();
+        check_seedable::<Philox4x32>();
+        check_seedable::<Philox4x64>();
+        check_seedable::<Threefry2x64>();
+    }
+
+    #[test]
+    fn test_from_seed() {
+        let mut seed = [0u8; 32];
+        for (i, b) in seed.iter_mut().enumerate() {
+            *b = if i % 8 == 0 { (i / 8 + 1) as u8 } else { 0u8 };
+        }
+        let mut prng1 = XoShiRo256StarStar::from_seed(seed);
+        let mut prng2 = XoShiRo256StarStar::new_from_state([1i64, 2i64, 3i64, 4i64]).unwrap();
+        for _ in 0..16 {
+            assert_eq!(prng1.next_long(), prng2.next_long());
+        }
+        // an all-zero seed gets repaired
+        let mut prng3 = XoShiRo256StarStar::from_seed([0u8; 32]);
+        assert_ne!(prng3.next_long() | prng3.next_long(), 0i64);
+        let mut prng4 = Lcg64Xor1024Mix::from_seed([0u8; 144]);
+        assert_ne!(prng4.next_long(), prng4.next_long());
+    }
+
+    #[test]
+    fn test_xoshiro_jump() {
+        // reference states from JDK 17's Xoshiro256PlusPlus (same linear engine)
+        let mut prng = XoShiRo256StarStar::new_from_state([1i64, 2i64, 3i64, 4i64]).unwrap();
+        prng.jump();
+        let mut expected = XoShiRo256StarStar::new_from_state([
+            -8324317625228856367i64,
+            8079205330032121950i64,
+            7289065458748526725i64,
+            -8969279818415701936i64,
+        ])
+        .unwrap();
+        for _ in 0..16 {
+            assert_eq!(prng.next_long(), expected.next_long());
+        }
+        let mut prng = XoShiRo256StarStar::new_from_state([1i64, 2i64, 3i64, 4i64]).unwrap();
+        prng.long_jump();
+        let mut expected = XoShiRo256StarStar::new_from_state([
+            678511610814637056i64,
+            -2596244294217022186i64,
+            6002989639035333134i64,
+            3559352929785830385i64,
+        ])
+        .unwrap();
+        for _ in 0..16 {
+            assert_eq!(prng.next_long(), expected.next_long());
+        }
+    }
+
+    #[test]
+    fn test_xoshiro_jumps() {
+        let mut prng = XoShiRo256StarStar::new_from(42i64);
+        let mut reference = prng.clone();
+        let mut streams = prng.jumps(3);
+        assert_eq!(streams.len(), 3);
+        for stream in streams.iter_mut() {
+            let mut expected = reference.clone();
+            for _ in 0..8 {
+                assert_eq!(stream.next_long(), expected.next_long());
+            }
+            reference.jump();
+        }
+        assert_eq!(prng.next_long(), reference.next_long());
+    }
+
+    #[test]
+    fn test_lcg64_xor_1024mix_split() {
+        let mut seed = [0i64; 16];
+        for (i, x) in seed.iter_mut().enumerate() {
+            *x = (i as i64 + 1i64).wrapping_mul(0x9e3779b97f4a7c15u64 as i64);
+        }
+        let mut parent = Lcg64Xor1024Mix::new_from_state(3i64, 0x1234567i64, seed).unwrap();
+        let mut child = parent.split();
+        // reference values from JDK 17's L64X1024MixRandom.split()
+        assert_eq!(child.next_long(), -2102800406349024826i64);
+        assert_eq!(child.next_long(), 4420500341598158179i64);
+        assert_eq!(child.next_long(), 8694690326496125840i64);
+        assert_eq!(parent.next_long(), -2109128763360605538i64);
+        assert_eq!(parent.next_long(), 1375910003055935834i64);
+        assert_eq!(parent.next_long(), 9117529862260263514i64);
+    }
+
+    fn check_splits<G: Splittable>(mut parent: G) {
+        let mut children: Vec<G> = parent.splits(4).collect();
+        assert_eq!(children.len(), 4);
+        let mut firsts: Vec<i64> = children.iter_mut().map(|g| g.next_long()).collect();
+        firsts.push(parent.next_long());
+        firsts.sort_unstable();
+        firsts.dedup();
+        assert_eq!(firsts.len(), 5);
+    }
+
+    #[test]
+    fn test_splits() {
+        check_splits(Stc64::new_from(42i64));
+        check_splits(Lcg64Xor1024Mix::new_from(42i64));
+        check_splits(SplitMix64::new_from(42i64));
+        check_splits(JavaSplittableRandom::new_from(42i64));
+        assert_eq!(Stc64::new().splits(7).len(), 7);
+    }
+
+    #[test]
+    fn test_advance() {
+        check_advance(XoShiRo256StarStar::new_from(42i64));
+        check_advance(Lcg64Xor1024Mix::new_from(42i64));
+        check_advance(SplitMix64::new_from(42i64));
+        check_advance(Xoshiro256PlusPlus::new_from(42i64));
+        check_advance(Xoshiro256Plus::new_from(42i64));
+        check_advance(Xoroshiro128PlusPlus::new_from(42i64));
+        check_advance(Xoroshiro128StarStar::new_from(42i64));
+        check_advance(Xoshiro512StarStar::new_from(42i64));
+        check_advance(Xoroshiro1024StarStar::new_from(42i64));
+        check_advance(Pcg32::new_from(42i64));
+        check_advance(Pcg64::new_from(42i64));
+        check_advance(Pcg64Dxsm::new_from(42i64));
+        check_advance(Philox4x32::new_from(42i64));
+        check_advance(Philox4x64::new_from(42i64));
+        check_advance(Threefry2x64::new_from(42i64));
+    }
+
+    #[test]
+    fn test_xoshiro_advance_jump() {
+        let mut prng1 = XoShiRo256StarStar::new_from(7i64);
+        let mut prng2 = prng1.clone();
+        prng1.jump();
+        prng2.advance(1u128 << 127);
+        prng2.advance(1u128 << 127);
+        for _ in 0..16 {
+            assert_eq!(prng1.next_long(), prng2.next_long());
+        }
+    }
+
+    #[test]
+    fn test_split_mix64_advance() {
+        let mut rng1 = SplitMix64::new_from(42i64);
+        let mut rng2 = SplitMix64::new_from(42i64);
+        for _ in 0..1000 {
+            rng1.next_long();
+        }
+        rng2.advance(1000u128 + (1u128 << 64));
+        assert_eq!(rng1.next_long(), rng2.next_long());
+    }
+
+    #[cfg(feature = "serde")]
+    fn check_serde<G>(mut prng: G)
+    where
+        G: PseudoRandom + serde::Serialize + serde::de::DeserializeOwned,
+    {
+        prng.next_long();
+        let json = serde_json::to_string(&prng).unwrap();
+        let mut restored: G = serde_json::from_str(&json).unwrap();
+        for _ in 0..32 {
+            assert_eq!(prng.next_long(), restored.next_long());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        check_serde(Stc64::new());
+        check_serde(XoShiRo256StarStar::new());
+        check_serde(SplitMix64::new());
+        check_serde(Xoshiro256PlusPlus::new());
+        check_serde(Xoshiro256Plus::new());
+        check_serde(Xoroshiro128PlusPlus::new());
+        check_serde(Xoroshiro128StarStar::new());
+        check_serde(Xoshiro512StarStar::new());
+        check_serde(Xoroshiro1024StarStar::new());
+        check_serde(Pcg32::new());
+        check_serde(Pcg64::new());
+        check_serde(Pcg64Dxsm::new());
+        check_serde(Philox4x32::new());
+        check_serde(Philox4x64::new());
+        check_serde(Threefry2x64::new());
+        let mut prng = Lcg64Xor1024Mix::new();
+        // make sure that pos isn't at its initial value
+        for _ in 0..5 {
+            prng.next_long();
+        }
+        check_serde(prng);
+    }
+
+    #[test]
+    fn test_state_bytes() {
+        let mut stc64 = Stc64::new();
+        let mut restored = Stc64::from_bytes(&stc64.to_bytes()).unwrap();
+        assert_eq!(stc64.next_long(), restored.next_long());
+        let mut xoshiro = XoShiRo256StarStar::new();
+        let mut restored = XoShiRo256StarStar::from_bytes(&xoshiro.to_bytes()).unwrap();
+        assert_eq!(xoshiro.next_long(), restored.next_long());
+        let mut lcg = Lcg64Xor1024Mix::new();
+        for n in 0..20 {
+            let mut restored = Lcg64Xor1024Mix::from_bytes(&lcg.to_bytes()).unwrap();
+            for _ in 0..n {
+                assert_eq!(lcg.next_long(), restored.next_long());
+            }
+        }
+        assert_eq!(
+            XoShiRo256StarStar::from_bytes(&stc64.to_bytes()).unwrap_err(),
+            StateFormatError::Algorithm(1u8)
+        );
+        let mut bytes = lcg.to_bytes();
+        bytes[42] ^= 0x10u8;
+        assert_eq!(
+            Lcg64Xor1024Mix::from_bytes(&bytes).unwrap_err(),
+            StateFormatError::Checksum
+        );
+    }
+
+    #[test]
+    fn test_state_bytes_stable() {
+        // the format must never change
+        let prng = XoShiRo256StarStar::new_from_state([1i64, 2i64, 3i64, -1i64]).unwrap();
+        let mut expected = vec![1u8, 2u8];
+        expected.extend_from_slice(&[1u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8]);
+        expected.extend_from_slice(&[2u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8]);
+        expected.extend_from_slice(&[3u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8]);
+        expected.extend_from_slice(&[0xffu8; 8]);
+        expected.extend_from_slice(&[0xd8u8, 0x61u8, 0x59u8, 0x4du8]);
+        assert_eq!(prng.to_bytes(), expected);
+    }
+
+    #[test]
+    fn test_lcg64_xor_1024mix_java() {
+        // reference values from JDK 17's L64X1024MixRandom(long)
+        let mut prng = Lcg64Xor1024Mix::new_java(42i64);
+        assert_eq!(prng.next_long(), -5600175640509174766i64);
+        assert_eq!(prng.next_long(), -4540056089906404195i64);
+        assert_eq!(prng.next_long(), 9215556704827072305i64);
+        let mut prng = Lcg64Xor1024Mix::new_java(-7i64);
+        assert_eq!(prng.next_long(), -6052085272053621321i64);
+        assert_eq!(prng.next_long(), 3508119921169008151i64);
+        assert_eq!(prng.next_long(), 5011150584896837955i64);
+    }
+
+    #[test]
+    fn test_java_bounded() {
+        // reference values from JDK 17's RandomGenerator methods
+        let mut prng = Xoshiro256PlusPlus::new_java(7i64);
+        assert_eq!(prng.next_long_up_to(1000i64), 565i64);
+        assert_eq!(prng.next_long_up_to(1000i64), 984i64);
+        assert_eq!(prng.next_long_up_to(1i64 << 40), 580776496719i64);
+        assert_eq!(prng.next_long_from_range(-1000i64, 1000i64), -827i64);
+        assert_eq!(prng.next_long_from_range(-1000i64, 1000i64), -10i64);
+        assert_eq!(
+            prng.next_long_from_range(i64::MIN + 1i64, i64::MAX),
+            -3980494052986180575i64
+        );
+        assert_eq!(prng.next_long_from_range(-5i64, 3i64), 0i64);
+        assert_eq!(
+            prng.next_double_from_range(-1.0f64, 3.0f64),
+            0.029603788609314563f64
+        );
+        assert_eq!(
+            prng.next_double_from_range(-1.0f64, 3.0f64),
+            1.045105485151037f64
+        );
+        assert_eq!(
+            prng.next_double_from_range(1e10f64, 1e12f64),
+            5.816914429552842e11f64
+        );
+        let mut prng = Lcg64Xor1024Mix::new_java(42i64);
+        assert_eq!(
+            prng.next_long_from_range(-3i64, 1000000007i64),
+            367346902i64
+        );
+    }
+
+    struct AllOnes;
+
+    impl PseudoRandom for AllOnes {
+        fn next_long(&mut self) -> i64 {
+            -1i64
+        }
+    }
+
+    #[test]
+    fn test_next_double_from_range_rounding() {
+        // (1 - 2^-53) * (bound - origin) + origin rounds up to bound here
+        assert_eq!(
+            AllOnes.next_double_from_range(1.0f64, 2.0f64),
+            1.9999999999999998f64
+        );
+        assert_eq!(
+            AllOnes.next_double_from_range(-3.0f64, -2.0f64),
+            -2.0000000000000004f64
+        );
+        assert_eq!(AllOnes.next_double_from_interval(1.0f64, 2.0f64), 2.0f64);
+    }
+
+    #[test]
+    fn test_stc64_from_state() {
+        let mut prng1 = Stc64::new_from_state([1i64, 2i64, 3i64, 4i64], 5i64).unwrap();
+        let mut prng2 = Stc64::new_from_state([1i64, 2i64, 3i64, 4i64], 5i64).unwrap();
+        for _ in 0..16 {
+            assert_eq!(prng1.next_long(), prng2.next_long());
+        }
+        assert_eq!(
+            Stc64::new_from_state([1i64, 2i64, 3i64, 4i64], 6i64).unwrap_err(),
+            IllegalStateError::EvenIncrement
+        );
+    }
 }
@@ -0,0 +1,1372 @@
+// Copyright 2022 Stefan Zobel
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! The remaining members of JDK 17's LXM family of generators. Each of them
+//! combines a linear congruential generator (LCG) with a Xor-based generator
+//! (XBG) and a mixing function. [Lcg64Xor1024Mix](crate::pseudo_random::Lcg64Xor1024Mix)
+//! lives in the `pseudo_random` module itself.
+
+use crate::bit_mix::{lea_mix32, lea_mix64, murmur_mix32, stafford_mix13};
+use crate::jump_ahead::{lcg_advance, lcg_advance128, x_pow_mod};
+use crate::pseudo_random::{check_nonzero, check_odd, expand_seed, seed_word, M};
+use crate::pseudo_random::{Advance, IllegalStateError, PseudoRandom, Seedable, Splittable};
+use crate::split_mix64::SplitMix64;
+use crate::split_mix64_seed::GOLDEN;
+use crate::xor_shift_128plus::XorShift128Plus;
+use crate::xoshiro::{jump_state, xoroshiro128, xoshiro256};
+use crate::xoshiro::{XOROSHIRO1024_CHAR_POLY, XOROSHIRO128_CHAR_POLY, XOSHIRO256_CHAR_POLY};
+use core::convert::TryInto;
+
+/*
+ * Multiplier used in the 32-bit LCG portion of Lcg32Xor64Mix. Chosen based on
+ * research by Sebastiano Vigna and Guy Steele (2019). The spectral scores for
+ * dimensions 2 through 8 for the multiplier 0xadb4a92d are [0.975884, 0.936244,
+ * 0.755793, 0.877642, 0.751300, 0.789333, 0.728869].
+ */
+const M32: i32 = 0xadb4a92du32 as i32;
+
+/*
+ * Low half of the multiplier used in the 128-bit LCG portion of the L128 family
+ * (the full multiplier is 2^64 + ML). Chosen based on research by Sebastiano
+ * Vigna and Guy Steele (2019). The spectral scores for dimensions 2 through 8
+ * for the multiplier 0x1d605bbb58c8abbfd are [0.991889, 0.907938, 0.830964,
+ * 0.837980, 0.780378, 0.797464, 0.761493].
+ */
+const ML: i64 = 0xd605bbb58c8abbfdu64 as i64;
+const M128: i128 = (1i128 << 64) | (ML as u64 as i128);
+
+// the golden ratio scaled to 32 bits
+const GOLDEN32: i32 = 0x9e3779b9u32 as i32;
+
+/*
+ * The lower 64 coefficients of the (monic, degree 64) characteristic
+ * polynomial of xoroshiro64's state transition matrix.
+ */
+const XOROSHIRO64_CHAR_POLY: [u64; 1] = [0x053be9da6e2286c1u64];
+
+// replaces an all-zero XBG state in the same way as the JDK does
+#[inline]
+fn ensure_nonzero(mut v: i64, x: &mut [i64]) {
+    if x.iter().all(|&w| w == 0i64) {
+        for w in x.iter_mut() {
+            v = v.wrapping_add(GOLDEN);
+            *w = stafford_mix13(v);
+        }
+    }
+}
+
+#[inline]
+fn seed_word32(seed: &[u8], i: usize) -> i32 {
+    i32::from_le_bytes(seed[4 * i..4 * i + 4].try_into().unwrap())
+}
+
+#[inline]
+fn seed_word128(seed: &[u8], i: usize) -> i128 {
+    i128::from_le_bytes(seed[8 * i..8 * i + 16].try_into().unwrap())
+}
+
+#[inline]
+fn high(v: i128) -> i64 {
+    (v >> 64) as i64
+}
+
+// the xoroshiro64 state transition on x0 (low half) and x1 (high half)
+#[inline]
+fn xoroshiro64(x: &mut [i64; 1]) {
+    let q0 = x[0] as i32;
+    let q1 = (x[0] >> 32) as i32 ^ q0;
+    let x0 = q0.rotate_left(26) ^ q1 ^ (q1 << 9);
+    let x1 = q1.rotate_left(13);
+    x[0] = ((x1 as i64) << 32) | (x0 as u32 as i64);
+}
+
+// jumps the xoroshiro128 state (x0, x1) ahead by n steps
+#[inline]
+fn advance_xoroshiro128(x0: &mut i64, x1: &mut i64, n: u128) {
+    let mut x = [*x0, *x1];
+    jump_state(&mut x, &x_pow_mod(n, &XOROSHIRO128_CHAR_POLY), |x| {
+        let [x0, x1] = x;
+        xoroshiro128(x0, x1);
+    });
+    *x0 = x[0];
+    *x1 = x[1];
+}
+
+/// The `L32X64MixRandom` algorithm from JDK 17 which uses a 32-bit linear
+/// congruential generator (LCG) as a first subgenerator and xoroshiro64 as
+/// a second subgenerator and then applies a 32-bit mixing function identified
+/// by Doug Lea. Its native output are 32-bit integers, a 64-bit value is made
+/// from two consecutive 32-bit outputs.
+///
+/// This generator has a 96-bit state and a period of
+/// 2<sup>32</sup>(2<sup>64</sup>&minus;1).
+///
+/// This is the generator with the smallest state in the LXM family.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lcg32Xor64Mix {
+    // the additive constant of the LCG, must be odd
+    a: i32,
+    // the LCG state
+    s: i32,
+    // the XBG state, at least one of x0 and x1 must be nonzero
+    x0: i32,
+    x1: i32,
+}
+
+impl PseudoRandom for Lcg32Xor64Mix {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        ((self.next_int() as i64) << 32) ^ (self.next_int() as i64)
+    }
+
+    #[inline]
+    fn next_int(&mut self) -> i32 {
+        let rnd = lea_mix32(self.s.wrapping_add(self.x0));
+        self.s = M32.wrapping_mul(self.s).wrapping_add(self.a);
+        let q0 = self.x0;
+        let q1 = self.x1 ^ q0;
+        self.x0 = q0.rotate_left(26) ^ q1 ^ (q1 << 9);
+        self.x1 = q1.rotate_left(13);
+        rnd
+    }
+}
+
+impl Lcg32Xor64Mix {
+    /// Creates a new [Lcg32Xor64Mix](Lcg32Xor64Mix) initialized with a random seed.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn new() -> Self {
+        Lcg32Xor64Mix::internal_new(&mut XorShift128Plus::new())
+    }
+
+    /// Creates a new [Lcg32Xor64Mix](Lcg32Xor64Mix) initialized with the given `seed`.
+    #[inline]
+    pub fn new_from(seed: i64) -> Self {
+        Lcg32Xor64Mix::internal_new(&mut XorShift128Plus::new_from(seed))
+    }
+
+    /// Creates a new [Lcg32Xor64Mix](Lcg32Xor64Mix) from its complete state:
+    /// the additive constant `a` and the state `s` of the LCG and the two
+    /// state words of the xoroshiro64 subgenerator. The result produces the
+    /// same stream as the JDK 17 constructor `L32X64MixRandom(a, s, x0, x1)`
+    /// does for an odd `a`.
+    ///
+    /// Returns an error if `a` is even or if both words of `x` are zero.
+    #[inline]
+    pub fn new_from_state(a: i32, s: i32, x: [i32; 2]) -> Result<Self, IllegalStateError> {
+        check_odd(a as i128)?;
+        check_nonzero(&[x[0] as i64, x[1] as i64])?;
+        Ok(Lcg32Xor64Mix {
+            a,
+            s,
+            x0: x[0],
+            x1: x[1],
+        })
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let w0 = seeder.next_long();
+        let w1 = seeder.next_long();
+        Lcg32Xor64Mix::with_fallback((w0 >> 32) as i32, w0 as i32, (w1 >> 32) as i32, w1 as i32)
+    }
+
+    // replaces an all-zero XBG state in the same way as the JDK does
+    #[inline]
+    fn with_fallback(a: i32, s: i32, mut x0: i32, mut x1: i32) -> Self {
+        if x0 | x1 == 0i32 {
+            let v = s.wrapping_add(GOLDEN32);
+            x0 = murmur_mix32(v);
+            x1 = murmur_mix32(v.wrapping_add(GOLDEN32));
+        }
+        Lcg32Xor64Mix {
+            a: a | 1i32,
+            s,
+            x0,
+            x1,
+        }
+    }
+}
+
+impl Splittable for Lcg32Xor64Mix {
+    /// Returns a new [Lcg32Xor64Mix](Lcg32Xor64Mix) in the same way as JDK 17's
+    /// `L32X64MixRandom.split()` does.
+    #[inline]
+    fn split(&mut self) -> Self {
+        let brine = self.next_long();
+        let s = self.next_int();
+        let x0 = self.next_int();
+        let x1 = self.next_int();
+        Lcg32Xor64Mix::with_fallback((brine as i32) << 1, s, x0, x1)
+    }
+}
+
+impl Lcg32Xor64Mix {
+    // advances by n calls to next_int()
+    #[inline]
+    fn advance_ints(&mut self, n: u128) {
+        self.s = lcg_advance(self.s as i64, M32 as i64, self.a as i64, n) as i32;
+        let mut x = [((self.x1 as i64) << 32) | (self.x0 as u32 as i64)];
+        jump_state(&mut x, &x_pow_mod(n, &XOROSHIRO64_CHAR_POLY), xoroshiro64);
+        self.x0 = x[0] as i32;
+        self.x1 = (x[0] >> 32) as i32;
+    }
+}
+
+impl Advance for Lcg32Xor64Mix {
+    #[inline]
+    fn advance(&mut self, n: u128) {
+        // a call to next_long() consumes two 32-bit outputs
+        self.advance_ints(n);
+        self.advance_ints(n);
+    }
+}
+
+impl Seedable for Lcg32Xor64Mix {
+    type Seed = [u8; 16];
+
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        let x = [seed_word32(&seed, 2), seed_word32(&seed, 3)];
+        if x[0] | x[1] == 0i32 {
+            return Self::seed_from_u64(0u64);
+        }
+        Lcg32Xor64Mix {
+            a: seed_word32(&seed, 0) | 1i32,
+            s: seed_word32(&seed, 1),
+            x0: x[0],
+            x1: x[1],
+        }
+    }
+
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_seed(expand_seed(SplitMix64::new_from(state as i64)))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn from_entropy() -> Self {
+        Self::from_seed(crate::seed::system_seed())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for Lcg32Xor64Mix {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The `L64X128MixRandom` algorithm from JDK 17 which uses a 64-bit linear
+/// congruential generator (LCG) as a first subgenerator and xoroshiro128 as
+/// a second subgenerator and then applies a 64-bit mixing function identified
+/// by Doug Lea.
+///
+/// This generator has a 192-bit state and a period of
+/// 2<sup>64</sup>(2<sup>128</sup>&minus;1).
+///
+/// This generator is 2-dimensionally equidistributed.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lcg64Xor128Mix {
+    // the additive constant of the LCG, must be odd
+    a: i64,
+    // the LCG state
+    s: i64,
+    // the XBG state, at least one of x0 and x1 must be nonzero
+    x0: i64,
+    x1: i64,
+}
+
+impl PseudoRandom for Lcg64Xor128Mix {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        let rnd = lea_mix64(self.s.wrapping_add(self.x0));
+        self.s = M.wrapping_mul(self.s).wrapping_add(self.a);
+        xoroshiro128(&mut self.x0, &mut self.x1);
+        rnd
+    }
+}
+
+impl Lcg64Xor128Mix {
+    /// Creates a new [Lcg64Xor128Mix](Lcg64Xor128Mix) initialized with a random seed.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn new() -> Self {
+        Lcg64Xor128Mix::internal_new(&mut XorShift128Plus::new())
+    }
+
+    /// Creates a new [Lcg64Xor128Mix](Lcg64Xor128Mix) initialized with the given `seed`.
+    #[inline]
+    pub fn new_from(seed: i64) -> Self {
+        Lcg64Xor128Mix::internal_new(&mut XorShift128Plus::new_from(seed))
+    }
+
+    /// Creates a new [Lcg64Xor128Mix](Lcg64Xor128Mix) from its complete state:
+    /// the additive constant `a` and the state `s` of the LCG and the two
+    /// state words of the xoroshiro128 subgenerator. The result produces the
+    /// same stream as the JDK 17 constructor `L64X128MixRandom(a, s, x0, x1)`
+    /// does for an odd `a`.
+    ///
+    /// Returns an error if `a` is even or if both words of `x` are zero.
+    #[inline]
+    pub fn new_from_state(a: i64, s: i64, x: [i64; 2]) -> Result<Self, IllegalStateError> {
+        check_odd(a as i128)?;
+        check_nonzero(&x)?;
+        Ok(Lcg64Xor128Mix {
+            a,
+            s,
+            x0: x[0],
+            x1: x[1],
+        })
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let a = seeder.next_long();
+        let s = seeder.next_long();
+        let x = [seeder.next_long(), seeder.next_long()];
+        Lcg64Xor128Mix::with_fallback(a, s, x)
+    }
+
+    #[inline]
+    fn with_fallback(a: i64, s: i64, mut x: [i64; 2]) -> Self {
+        ensure_nonzero(s, &mut x);
+        Lcg64Xor128Mix {
+            a: a | 1i64,
+            s,
+            x0: x[0],
+            x1: x[1],
+        }
+    }
+}
+
+impl Splittable for Lcg64Xor128Mix {
+    /// Returns a new [Lcg64Xor128Mix](Lcg64Xor128Mix) in the same way as JDK 17's
+    /// `L64X128MixRandom.split()` does.
+    #[inline]
+    fn split(&mut self) -> Self {
+        let a = self.next_long() << 1;
+        let s = self.next_long();
+        let x = [self.next_long(), self.next_long()];
+        Lcg64Xor128Mix::with_fallback(a, s, x)
+    }
+}
+
+impl Advance for Lcg64Xor128Mix {
+    #[inline]
+    fn advance(&mut self, n: u128) {
+        self.s = lcg_advance(self.s, M, self.a, n);
+        advance_xoroshiro128(&mut self.x0, &mut self.x1, n);
+    }
+}
+
+impl Seedable for Lcg64Xor128Mix {
+    type Seed = [u8; 32];
+
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        let x = [seed_word(&seed, 2), seed_word(&seed, 3)];
+        Lcg64Xor128Mix::new_from_state(seed_word(&seed, 0) | 1i64, seed_word(&seed, 1), x)
+            .unwrap_or_else(|_| Self::seed_from_u64(0u64))
+    }
+
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_seed(expand_seed(SplitMix64::new_from(state as i64)))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn from_entropy() -> Self {
+        Self::from_seed(crate::seed::system_seed())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for Lcg64Xor128Mix {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The `L64X128StarStarRandom` algorithm from JDK 17 which uses a 64-bit
+/// linear congruential generator (LCG) as a first subgenerator and xoroshiro128
+/// as a second subgenerator and then applies the `**` scrambler of xoshiro256**
+/// instead of a mixing function.
+///
+/// This generator has a 192-bit state and a period of
+/// 2<sup>64</sup>(2<sup>128</sup>&minus;1).
+///
+/// This generator is 2-dimensionally equidistributed.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lcg64Xor128StarStar {
+    // the additive constant of the LCG, must be odd
+    a: i64,
+    // the LCG state
+    s: i64,
+    // the XBG state, at least one of x0 and x1 must be nonzero
+    x0: i64,
+    x1: i64,
+}
+
+impl PseudoRandom for Lcg64Xor128StarStar {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        let rnd = self
+            .s
+            .wrapping_add(self.x0)
+            .wrapping_mul(5i64)
+            .rotate_left(7)
+            .wrapping_mul(9i64);
+        self.s = M.wrapping_mul(self.s).wrapping_add(self.a);
+        xoroshiro128(&mut self.x0, &mut self.x1);
+        rnd
+    }
+}
+
+impl Lcg64Xor128StarStar {
+    /// Creates a new [Lcg64Xor128StarStar](Lcg64Xor128StarStar) initialized with a random seed.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn new() -> Self {
+        Lcg64Xor128StarStar::internal_new(&mut XorShift128Plus::new())
+    }
+
+    /// Creates a new [Lcg64Xor128StarStar](Lcg64Xor128StarStar) initialized with the given `seed`.
+    #[inline]
+    pub fn new_from(seed: i64) -> Self {
+        Lcg64Xor128StarStar::internal_new(&mut XorShift128Plus::new_from(seed))
+    }
+
+    /// Creates a new [Lcg64Xor128StarStar](Lcg64Xor128StarStar) from its complete
+    /// state: the additive constant `a` and the state `s` of the LCG and the two
+    /// state words of the xoroshiro128 subgenerator. The result produces the
+    /// same stream as the JDK 17 constructor `L64X128StarStarRandom(a, s, x0, x1)`
+    /// does for an odd `a`.
+    ///
+    /// Returns an error if `a` is even or if both words of `x` are zero.
+    #[inline]
+    pub fn new_from_state(a: i64, s: i64, x: [i64; 2]) -> Result<Self, IllegalStateError> {
+        check_odd(a as i128)?;
+        check_nonzero(&x)?;
+        Ok(Lcg64Xor128StarStar {
+            a,
+            s,
+            x0: x[0],
+            x1: x[1],
+        })
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let a = seeder.next_long();
+        let s = seeder.next_long();
+        let x = [seeder.next_long(), seeder.next_long()];
+        Lcg64Xor128StarStar::with_fallback(a, s, x)
+    }
+
+    #[inline]
+    fn with_fallback(a: i64, s: i64, mut x: [i64; 2]) -> Self {
+        ensure_nonzero(s, &mut x);
+        Lcg64Xor128StarStar {
+            a: a | 1i64,
+            s,
+            x0: x[0],
+            x1: x[1],
+        }
+    }
+}
+
+impl Splittable for Lcg64Xor128StarStar {
+    /// Returns a new [Lcg64Xor128StarStar](Lcg64Xor128StarStar) in the same way as
+    /// JDK 17's `L64X128StarStarRandom.split()` does.
+    #[inline]
+    fn split(&mut self) -> Self {
+        let a = self.next_long() << 1;
+        let s = self.next_long();
+        let x = [self.next_long(), self.next_long()];
+        Lcg64Xor128StarStar::with_fallback(a, s, x)
+    }
+}
+
+impl Advance for Lcg64Xor128StarStar {
+    #[inline]
+    fn advance(&mut self, n: u128) {
+        self.s = lcg_advance(self.s, M, self.a, n);
+        advance_xoroshiro128(&mut self.x0, &mut self.x1, n);
+    }
+}
+
+impl Seedable for Lcg64Xor128StarStar {
+    type Seed = [u8; 32];
+
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        let x = [seed_word(&seed, 2), seed_word(&seed, 3)];
+        Lcg64Xor128StarStar::new_from_state(seed_word(&seed, 0) | 1i64, seed_word(&seed, 1), x)
+            .unwrap_or_else(|_| Self::seed_from_u64(0u64))
+    }
+
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_seed(expand_seed(SplitMix64::new_from(state as i64)))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn from_entropy() -> Self {
+        Self::from_seed(crate::seed::system_seed())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for Lcg64Xor128StarStar {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The `L64X256MixRandom` algorithm from JDK 17 which uses a 64-bit linear
+/// congruential generator (LCG) as a first subgenerator and xoshiro256 as
+/// a second subgenerator and then applies a 64-bit mixing function identified
+/// by Doug Lea.
+///
+/// This generator has a 320-bit state and a period of
+/// 2<sup>64</sup>(2<sup>256</sup>&minus;1).
+///
+/// This generator is 4-dimensionally equidistributed.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lcg64Xor256Mix {
+    // the additive constant of the LCG, must be odd
+    a: i64,
+    // the LCG state
+    s: i64,
+    // the XBG state, at least one of the 4 elements must be nonzero
+    x: [i64; 4],
+}
+
+impl PseudoRandom for Lcg64Xor256Mix {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        let rnd = lea_mix64(self.s.wrapping_add(self.x[0]));
+        self.s = M.wrapping_mul(self.s).wrapping_add(self.a);
+        xoshiro256(&mut self.x);
+        rnd
+    }
+}
+
+impl Lcg64Xor256Mix {
+    /// Creates a new [Lcg64Xor256Mix](Lcg64Xor256Mix) initialized with a random seed.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn new() -> Self {
+        Lcg64Xor256Mix::internal_new(&mut XorShift128Plus::new())
+    }
+
+    /// Creates a new [Lcg64Xor256Mix](Lcg64Xor256Mix) initialized with the given `seed`.
+    #[inline]
+    pub fn new_from(seed: i64) -> Self {
+        Lcg64Xor256Mix::internal_new(&mut XorShift128Plus::new_from(seed))
+    }
+
+    /// Creates a new [Lcg64Xor256Mix](Lcg64Xor256Mix) from its complete state:
+    /// the additive constant `a` and the state `s` of the LCG and the four
+    /// state words of the xoshiro256 subgenerator. The result produces the
+    /// same stream as the JDK 17 constructor `L64X256MixRandom(a, s, x0, x1, x2, x3)`
+    /// does for an odd `a`.
+    ///
+    /// Returns an error if `a` is even or if all four words of `x` are zero.
+    #[inline]
+    pub fn new_from_state(a: i64, s: i64, x: [i64; 4]) -> Result<Self, IllegalStateError> {
+        check_odd(a as i128)?;
+        check_nonzero(&x)?;
+        Ok(Lcg64Xor256Mix { a, s, x })
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let a = seeder.next_long();
+        let s = seeder.next_long();
+        let mut x = [0i64; 4];
+        for w in x.iter_mut() {
+            *w = seeder.next_long();
+        }
+        Lcg64Xor256Mix::with_fallback(a, s, x)
+    }
+
+    #[inline]
+    fn with_fallback(a: i64, s: i64, mut x: [i64; 4]) -> Self {
+        ensure_nonzero(s, &mut x);
+        Lcg64Xor256Mix { a: a | 1i64, s, x }
+    }
+}
+
+impl Splittable for Lcg64Xor256Mix {
+    /// Returns a new [Lcg64Xor256Mix](Lcg64Xor256Mix) in the same way as JDK 17's
+    /// `L64X256MixRandom.split()` does.
+    #[inline]
+    fn split(&mut self) -> Self {
+        let a = self.next_long() << 1;
+        let s = self.next_long();
+        let mut x = [0i64; 4];
+        for w in x.iter_mut() {
+            *w = self.next_long();
+        }
+        Lcg64Xor256Mix::with_fallback(a, s, x)
+    }
+}
+
+impl Advance for Lcg64Xor256Mix {
+    #[inline]
+    fn advance(&mut self, n: u128) {
+        self.s = lcg_advance(self.s, M, self.a, n);
+        jump_state(
+            &mut self.x,
+            &x_pow_mod(n, &XOSHIRO256_CHAR_POLY),
+            xoshiro256,
+        );
+    }
+}
+
+impl Seedable for Lcg64Xor256Mix {
+    type Seed = [u8; 48];
+
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut x = [0i64; 4];
+        for (i, w) in x.iter_mut().enumerate() {
+            *w = seed_word(&seed, i + 2);
+        }
+        Lcg64Xor256Mix::new_from_state(seed_word(&seed, 0) | 1i64, seed_word(&seed, 1), x)
+            .unwrap_or_else(|_| Self::seed_from_u64(0u64))
+    }
+
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_seed(expand_seed(SplitMix64::new_from(state as i64)))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn from_entropy() -> Self {
+        Self::from_seed(crate::seed::system_seed())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for Lcg64Xor256Mix {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The `L128X128MixRandom` algorithm from JDK 17 which uses a 128-bit linear
+/// congruential generator (LCG) as a first subgenerator and xoroshiro128 as
+/// a second subgenerator and then applies a 64-bit mixing function identified
+/// by Doug Lea.
+///
+/// This generator has a 256-bit state and a period of
+/// 2<sup>128</sup>(2<sup>128</sup>&minus;1).
+///
+/// This generator is 1-dimensionally equidistributed.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lcg128Xor128Mix {
+    // the additive constant of the LCG, must be odd
+    a: i128,
+    // the LCG state
+    s: i128,
+    // the XBG state, at least one of x0 and x1 must be nonzero
+    x0: i64,
+    x1: i64,
+}
+
+impl PseudoRandom for Lcg128Xor128Mix {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        let rnd = lea_mix64(high(self.s).wrapping_add(self.x0));
+        self.s = M128.wrapping_mul(self.s).wrapping_add(self.a);
+        xoroshiro128(&mut self.x0, &mut self.x1);
+        rnd
+    }
+}
+
+impl Lcg128Xor128Mix {
+    /// Creates a new [Lcg128Xor128Mix](Lcg128Xor128Mix) initialized with a random seed.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn new() -> Self {
+        Lcg128Xor128Mix::internal_new(&mut XorShift128Plus::new())
+    }
+
+    /// Creates a new [Lcg128Xor128Mix](Lcg128Xor128Mix) initialized with the given `seed`.
+    #[inline]
+    pub fn new_from(seed: i64) -> Self {
+        Lcg128Xor128Mix::internal_new(&mut XorShift128Plus::new_from(seed))
+    }
+
+    /// Creates a new [Lcg128Xor128Mix](Lcg128Xor128Mix) from its complete state:
+    /// the 128-bit additive constant `a` and the 128-bit state `s` of the LCG and
+    /// the two state words of the xoroshiro128 subgenerator. The result produces
+    /// the same stream as the JDK 17 constructor
+    /// `L128X128MixRandom(ah, al, sh, sl, x0, x1)` does for an odd `al` where
+    /// `ah`, `al` and `sh`, `sl` are the high and low halves of `a` and `s`.
+    ///
+    /// Returns an error if `a` is even or if both words of `x` are zero.
+    #[inline]
+    pub fn new_from_state(a: i128, s: i128, x: [i64; 2]) -> Result<Self, IllegalStateError> {
+        check_odd(a)?;
+        check_nonzero(&x)?;
+        Ok(Lcg128Xor128Mix {
+            a,
+            s,
+            x0: x[0],
+            x1: x[1],
+        })
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let ah = seeder.next_long();
+        let al = seeder.next_long();
+        let sh = seeder.next_long();
+        let sl = seeder.next_long();
+        let x = [seeder.next_long(), seeder.next_long()];
+        Lcg128Xor128Mix::with_fallback(ah, al, sh, sl, x)
+    }
+
+    #[inline]
+    fn with_fallback(ah: i64, al: i64, sh: i64, sl: i64, mut x: [i64; 2]) -> Self {
+        ensure_nonzero(sh, &mut x);
+        Lcg128Xor128Mix {
+            a: join(ah, al | 1i64),
+            s: join(sh, sl),
+            x0: x[0],
+            x1: x[1],
+        }
+    }
+}
+
+#[inline]
+fn join(high: i64, low: i64) -> i128 {
+    ((high as i128) << 64) | (low as u64 as i128)
+}
+
+impl Splittable for Lcg128Xor128Mix {
+    /// Returns a new [Lcg128Xor128Mix](Lcg128Xor128Mix) in the same way as JDK 17's
+    /// `L128X128MixRandom.split()` does.
+    #[inline]
+    fn split(&mut self) -> Self {
+        let al = self.next_long() << 1;
+        let ah = self.next_long();
+        let sh = self.next_long();
+        let sl = self.next_long();
+        let x = [self.next_long(), self.next_long()];
+        Lcg128Xor128Mix::with_fallback(ah, al, sh, sl, x)
+    }
+}
+
+impl Advance for Lcg128Xor128Mix {
+    #[inline]
+    fn advance(&mut self, n: u128) {
+        self.s = lcg_advance128(self.s, M128, self.a, n);
+        advance_xoroshiro128(&mut self.x0, &mut self.x1, n);
+    }
+}
+
+impl Seedable for Lcg128Xor128Mix {
+    type Seed = [u8; 48];
+
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        let x = [seed_word(&seed, 4), seed_word(&seed, 5)];
+        Lcg128Xor128Mix::new_from_state(seed_word128(&seed, 0) | 1i128, seed_word128(&seed, 2), x)
+            .unwrap_or_else(|_| Self::seed_from_u64(0u64))
+    }
+
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_seed(expand_seed(SplitMix64::new_from(state as i64)))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn from_entropy() -> Self {
+        Self::from_seed(crate::seed::system_seed())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for Lcg128Xor128Mix {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The `L128X256MixRandom` algorithm from JDK 17 which uses a 128-bit linear
+/// congruential generator (LCG) as a first subgenerator and xoshiro256 as
+/// a second subgenerator and then applies a 64-bit mixing function identified
+/// by Doug Lea.
+///
+/// This generator has a 384-bit state and a period of
+/// 2<sup>128</sup>(2<sup>256</sup>&minus;1).
+///
+/// This generator is 1-dimensionally equidistributed.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lcg128Xor256Mix {
+    // the additive constant of the LCG, must be odd
+    a: i128,
+    // the LCG state
+    s: i128,
+    // the XBG state, at least one of the 4 elements must be nonzero
+    x: [i64; 4],
+}
+
+impl PseudoRandom for Lcg128Xor256Mix {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        let rnd = lea_mix64(high(self.s).wrapping_add(self.x[0]));
+        self.s = M128.wrapping_mul(self.s).wrapping_add(self.a);
+        xoshiro256(&mut self.x);
+        rnd
+    }
+}
+
+impl Lcg128Xor256Mix {
+    /// Creates a new [Lcg128Xor256Mix](Lcg128Xor256Mix) initialized with a random seed.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn new() -> Self {
+        Lcg128Xor256Mix::internal_new(&mut XorShift128Plus::new())
+    }
+
+    /// Creates a new [Lcg128Xor256Mix](Lcg128Xor256Mix) initialized with the given `seed`.
+    #[inline]
+    pub fn new_from(seed: i64) -> Self {
+        Lcg128Xor256Mix::internal_new(&mut XorShift128Plus::new_from(seed))
+    }
+
+    /// Creates a new [Lcg128Xor256Mix](Lcg128Xor256Mix) from its complete state:
+    /// the 128-bit additive constant `a` and the 128-bit state `s` of the LCG and
+    /// the four state words of the xoshiro256 subgenerator. The result produces
+    /// the same stream as the JDK 17 constructor
+    /// `L128X256MixRandom(ah, al, sh, sl, x0, x1, x2, x3)` does for an odd `al`
+    /// where `ah`, `al` and `sh`, `sl` are the high and low halves of `a` and `s`.
+    ///
+    /// Returns an error if `a` is even or if all four words of `x` are zero.
+    #[inline]
+    pub fn new_from_state(a: i128, s: i128, x: [i64; 4]) -> Result<Self, IllegalStateError> {
+        check_odd(a)?;
+        check_nonzero(&x)?;
+        Ok(Lcg128Xor256Mix { a, s, x })
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let ah = seeder.next_long();
+        let al = seeder.next_long();
+        let sh = seeder.next_long();
+        let sl = seeder.next_long();
+        let mut x = [0i64; 4];
+        for w in x.iter_mut() {
+            *w = seeder.next_long();
+        }
+        Lcg128Xor256Mix::with_fallback(ah, al, sh, sl, x)
+    }
+
+    #[inline]
+    fn with_fallback(ah: i64, al: i64, sh: i64, sl: i64, mut x: [i64; 4]) -> Self {
+        ensure_nonzero(sh, &mut x);
+        Lcg128Xor256Mix {
+            a: join(ah, al | 1i64),
+            s: join(sh, sl),
+            x,
+        }
+    }
+}
+
+impl Splittable for Lcg128Xor256Mix {
+    /// Returns a new [Lcg128Xor256Mix](Lcg128Xor256Mix) in the same way as JDK 17's
+    /// `L128X256MixRandom.split()` does.
+    #[inline]
+    fn split(&mut self) -> Self {
+        let al = self.next_long() << 1;
+        let ah = self.next_long();
+        let sh = self.next_long();
+        let sl = self.next_long();
+        let mut x = [0i64; 4];
+        for w in x.iter_mut() {
+            *w = self.next_long();
+        }
+        Lcg128Xor256Mix::with_fallback(ah, al, sh, sl, x)
+    }
+}
+
+impl Advance for Lcg128Xor256Mix {
+    #[inline]
+    fn advance(&mut self, n: u128) {
+        self.s = lcg_advance128(self.s, M128, self.a, n);
+        jump_state(
+            &mut self.x,
+            &x_pow_mod(n, &XOSHIRO256_CHAR_POLY),
+            xoshiro256,
+        );
+    }
+}
+
+impl Seedable for Lcg128Xor256Mix {
+    type Seed = [u8; 64];
+
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut x = [0i64; 4];
+        for (i, w) in x.iter_mut().enumerate() {
+            *w = seed_word(&seed, i + 4);
+        }
+        Lcg128Xor256Mix::new_from_state(seed_word128(&seed, 0) | 1i128, seed_word128(&seed, 2), x)
+            .unwrap_or_else(|_| Self::seed_from_u64(0u64))
+    }
+
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_seed(expand_seed(SplitMix64::new_from(state as i64)))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn from_entropy() -> Self {
+        Self::from_seed(crate::seed::system_seed())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for Lcg128Xor256Mix {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The `L128X1024MixRandom` algorithm from JDK 17 which uses a 128-bit linear
+/// congruential generator (LCG) as a first subgenerator and xoroshiro1024 as
+/// a second subgenerator and then applies a 64-bit mixing function identified
+/// by Doug Lea.
+///
+/// This generator has a 1152-bit state and a period of
+/// 2<sup>128</sup>(2<sup>1024</sup>&minus;1).
+///
+/// This generator is 1-dimensionally equidistributed.
+///
+/// This is the generator with the largest state in the LXM family.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lcg128Xor1024Mix {
+    // the additive constant of the LCG, must be odd
+    a: i128,
+    // the LCG state
+    s: i128,
+    /*
+     * The state of the XBG: pos is the rotating pointer into the array seed.
+     * At least one of the 16 elements of the array seed must be nonzero.
+     */
+    pos: usize,
+    seed: [i64; 16],
+}
+
+impl PseudoRandom for Lcg128Xor1024Mix {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        // xoroshiro1024: part 1
+        let p = self.pos;
+        let mut s15 = self.seed[p];
+        self.pos = (p + 1) & 15;
+        let s0 = self.seed[self.pos];
+        // compute result
+        let rnd = lea_mix64(high(self.s).wrapping_add(s0));
+        // update LCG sub-generator
+        self.s = M128.wrapping_mul(self.s).wrapping_add(self.a);
+        // xoroshiro1024: part 2
+        s15 ^= s0;
+        self.seed[p] = s0.rotate_left(25) ^ s15 ^ (s15 << 27);
+        self.seed[self.pos] = s15.rotate_left(36);
+        rnd
+    }
+}
+
+impl Lcg128Xor1024Mix {
+    #[inline]
+    fn xbg_step(&mut self) {
+        let p = self.pos;
+        let mut s15 = self.seed[p];
+        self.pos = (p + 1) & 15;
+        let s0 = self.seed[self.pos];
+        s15 ^= s0;
+        self.seed[p] = s0.rotate_left(25) ^ s15 ^ (s15 << 27);
+        self.seed[self.pos] = s15.rotate_left(36);
+    }
+
+    /// Creates a new [Lcg128Xor1024Mix](Lcg128Xor1024Mix) initialized with a random seed.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn new() -> Self {
+        Lcg128Xor1024Mix::internal_new(&mut XorShift128Plus::new())
+    }
+
+    /// Creates a new [Lcg128Xor1024Mix](Lcg128Xor1024Mix) initialized with the given `seed`.
+    #[inline]
+    pub fn new_from(seed: i64) -> Self {
+        Lcg128Xor1024Mix::internal_new(&mut XorShift128Plus::new_from(seed))
+    }
+
+    /// Creates a new [Lcg128Xor1024Mix](Lcg128Xor1024Mix) from its complete state:
+    /// the 128-bit additive constant `a` and the 128-bit state `s` of the LCG and
+    /// the 16 state words of the xoroshiro1024 subgenerator. The result produces
+    /// the same stream as the JDK 17 constructor
+    /// `L128X1024MixRandom(ah, al, sh, sl, x0, ..., x15)` does for an odd `al`
+    /// where `ah`, `al` and `sh`, `sl` are the high and low halves of `a` and `s`.
+    ///
+    /// Returns an error if `a` is even or if all 16 words of `seed` are zero.
+    #[inline]
+    pub fn new_from_state(a: i128, s: i128, seed: [i64; 16]) -> Result<Self, IllegalStateError> {
+        check_odd(a)?;
+        check_nonzero(&seed)?;
+        Ok(Lcg128Xor1024Mix {
+            a,
+            s,
+            pos: 15usize,
+            seed,
+        })
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let ah = seeder.next_long();
+        let al = seeder.next_long();
+        let sh = seeder.next_long();
+        let sl = seeder.next_long();
+        let mut seed = [0i64; 16];
+        for w in seed.iter_mut() {
+            *w = seeder.next_long();
+        }
+        Lcg128Xor1024Mix::with_fallback(ah, al, sh, sl, seed)
+    }
+
+    #[inline]
+    fn with_fallback(ah: i64, al: i64, sh: i64, sl: i64, mut seed: [i64; 16]) -> Self {
+        ensure_nonzero(sh, &mut seed);
+        Lcg128Xor1024Mix {
+            a: join(ah, al | 1i64),
+            s: join(sh, sl),
+            pos: 15usize,
+            seed,
+        }
+    }
+}
+
+impl Splittable for Lcg128Xor1024Mix {
+    /// Returns a new [Lcg128Xor1024Mix](Lcg128Xor1024Mix) in the same way as JDK 17's
+    /// `L128X1024MixRandom.split()` does.
+    #[inline]
+    fn split(&mut self) -> Self {
+        let al = self.next_long() << 1;
+        let ah = self.next_long();
+        let sh = self.next_long();
+        let sl = self.next_long();
+        let mut seed = [0i64; 16];
+        for w in seed.iter_mut() {
+            *w = self.next_long();
+        }
+        Lcg128Xor1024Mix::with_fallback(ah, al, sh, sl, seed)
+    }
+}
+
+impl Advance for Lcg128Xor1024Mix {
+    #[inline]
+    fn advance(&mut self, n: u128) {
+        self.s = lcg_advance128(self.s, M128, self.a, n);
+        let poly = x_pow_mod(n, &XOROSHIRO1024_CHAR_POLY);
+        let mut t = [0i64; 16];
+        for &word in poly.iter() {
+            for b in 0..64 {
+                if (word >> b) & 1u64 != 0u64 {
+                    for (j, x) in t.iter_mut().enumerate() {
+                        *x ^= self.seed[(j + self.pos) & 15];
+                    }
+                }
+                self.xbg_step();
+            }
+        }
+        for (j, &x) in t.iter().enumerate() {
+            self.seed[(j + self.pos) & 15] = x;
+        }
+    }
+}
+
+impl Seedable for Lcg128Xor1024Mix {
+    type Seed = [u8; 160];
+
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut xbg = [0i64; 16];
+        for (i, w) in xbg.iter_mut().enumerate() {
+            *w = seed_word(&seed, i + 4);
+        }
+        Lcg128Xor1024Mix::new_from_state(
+            seed_word128(&seed, 0) | 1i128,
+            seed_word128(&seed, 2),
+            xbg,
+        )
+        .unwrap_or_else(|_| Self::seed_from_u64(0u64))
+    }
+
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_seed(expand_seed(SplitMix64::new_from(state as i64)))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn from_entropy() -> Self {
+        Self::from_seed(crate::seed::system_seed())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for Lcg128Xor1024Mix {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod lxm_tests {
+    use super::*;
+    use crate::test_util::check_advance;
+
+    const G: i64 = GOLDEN;
+
+    fn golden_words<const N: usize>() -> [i64; N] {
+        let mut x = [0i64; N];
+        for (i, w) in x.iter_mut().enumerate() {
+            *w = (i as i64 + 1i64).wrapping_mul(G);
+        }
+        x
+    }
+
+    // compares against reference values computed by the JDK 17 implementation
+    fn check_reference<R: Splittable>(mut prng: R, longs: &[i64], child: &[i64], parent: &[i64]) {
+        for &l in longs {
+            assert_eq!(prng.next_long(), l);
+        }
+        let mut split = prng.split();
+        for &l in child {
+            assert_eq!(split.next_long(), l);
+        }
+        for &l in parent {
+            assert_eq!(prng.next_long(), l);
+        }
+    }
+
+    #[test]
+    fn test_lcg32_xor64_mix() {
+        let mut prng =
+            Lcg32Xor64Mix::new_from_state(0x12345i32, 0x6789i32, [0x1357i32, 0x2468i32]).unwrap();
+        assert_eq!(prng.next_int(), 1923878050i32);
+        assert_eq!(prng.next_int(), -735170393i32);
+        assert_eq!(prng.next_int(), 1927488608i32);
+        check_reference(
+            Lcg32Xor64Mix::new_from_state(0x12345i32, 0x6789i32, [0x1357i32, 0x2468i32]).unwrap(),
+            &[
+                -8262993306977423193i64,
+                -8278500535119958296i64,
+                -8523835280271302921i64,
+                4028407780642573659i64,
+            ],
+            &[-8084582170060890032i64, 301724124215601632i64],
+            &[-4762015667981468815i64, 4317601151900798869i64],
+        );
+        assert_eq!(
+            Lcg32Xor64Mix::new_from_state(2i32, 0i32, [1i32, 1i32]).unwrap_err(),
+            IllegalStateError::EvenIncrement
+        );
+        assert_eq!(
+            Lcg32Xor64Mix::new_from_state(1i32, 0i32, [0i32, 0i32]).unwrap_err(),
+            IllegalStateError::AllZero
+        );
+    }
+
+    #[test]
+    fn test_lcg64_xor128_mix() {
+        check_reference(
+            Lcg64Xor128Mix::new_from_state(3i64, 0x1234567i64, golden_words()).unwrap(),
+            &[
+                -2714861467225706635i64,
+                -7637626610284938560i64,
+                8122470303709020877i64,
+                -2781396324275924602i64,
+            ],
+            &[8857446162573378768i64, -6431228997041050408i64],
+            &[-2076220501660923323i64, -2381583252140834165i64],
+        );
+        assert_eq!(
+            Lcg64Xor128Mix::new_from_state(3i64, 0i64, [0i64; 2]).unwrap_err(),
+            IllegalStateError::AllZero
+        );
+    }
+
+    #[test]
+    fn test_lcg64_xor128_star_star() {
+        check_reference(
+            Lcg64Xor128StarStar::new_from_state(3i64, 0x1234567i64, golden_words()).unwrap(),
+            &[
+                -2291542994429319581i64,
+                4408569313414072708i64,
+                -7988738436663262181i64,
+                8666780394712983530i64,
+            ],
+            &[-6713059656997224187i64, -6460204412799369785i64],
+            &[-2497056590930124247i64, 4953402481536908111i64],
+        );
+    }
+
+    #[test]
+    fn test_lcg64_xor256_mix() {
+        check_reference(
+            Lcg64Xor256Mix::new_from_state(3i64, 0x1234567i64, golden_words()).unwrap(),
+            &[
+                -2714861467225706635i64,
+                -2639282508974539952i64,
+                -2145914787663598862i64,
+                3945395440564013043i64,
+            ],
+            &[3556377669402328028i64, 4361782573349462946i64],
+            &[-4588118623729716284i64, 7812231553560455075i64],
+        );
+    }
+
+    #[test]
+    fn test_lcg128_xor128_mix() {
+        check_reference(
+            Lcg128Xor128Mix::new_from_state(
+                join(0x1111i64, 3i64),
+                join(0x2222i64, 0x1234567i64),
+                golden_words(),
+            )
+            .unwrap(),
+            &[
+                -5314474660294948077i64,
+                1660508566257408423i64,
+                -6718760317235361794i64,
+                8496429360843981661i64,
+            ],
+            &[-3032318732451639005i64, 2789960424195788028i64],
+            &[2258169896848761098i64, 7920301263449381273i64],
+        );
+        assert_eq!(
+            Lcg128Xor128Mix::new_from_state(join(1i64, 2i64), 0i128, golden_words()).unwrap_err(),
+            IllegalStateError::EvenIncrement
+        );
+    }
+
+    #[test]
+    fn test_lcg128_xor256_mix() {
+        check_reference(
+            Lcg128Xor256Mix::new_from_state(
+                join(0x1111i64, 3i64),
+                join(0x2222i64, 0x1234567i64),
+                golden_words(),
+            )
+            .unwrap(),
+            &[
+                -5314474660294948077i64,
+                1417760725185597224i64,
+                -4449706453483092448i64,
+                -7866346357213788639i64,
+            ],
+            &[3367649070145933187i64, -4930996266526852181i64],
+            &[5381146850020373599i64, 4716486767588336767i64],
+        );
+    }
+
+    #[test]
+    fn test_lcg128_xor1024_mix() {
+        check_reference(
+            Lcg128Xor1024Mix::new_from_state(
+                join(0x1111i64, 3i64),
+                join(0x2222i64, 0x1234567i64),
+                golden_words(),
+            )
+            .unwrap(),
+            &[
+                -5314474660294948077i64,
+                -5492782837805425823i64,
+                3155995974066430741i64,
+                -4612253120886479209i64,
+            ],
+            &[7219901019485756490i64, -8352294541885953175i64],
+            &[-3369603799901509634i64, 3158803473567036617i64],
+        );
+    }
+
+    #[cfg(feature = "std")]
+    fn check_seeding<R: PseudoRandom + Seedable + Default>(mut prng: R) {
+        let mut prng1 = R::seed_from_u64(7u64);
+        let mut prng2 = R::seed_from_u64(7u64);
+        let mut prng3 = R::default();
+        for _ in 0..8 {
+            let l = prng1.next_long();
+            assert_eq!(l, prng2.next_long());
+            assert_ne!(l, prng3.next_long());
+            assert_ne!(prng.next_long(), prng.next_long());
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_seeding() {
+        check_seeding(Lcg32Xor64Mix::new_from(1i64));
+        check_seeding(Lcg64Xor128Mix::new_from(1i64));
+        check_seeding(Lcg64Xor128StarStar::new_from(1i64));
+        check_seeding(Lcg64Xor256Mix::new_from(1i64));
+        check_seeding(Lcg128Xor128Mix::new_from(1i64));
+        check_seeding(Lcg128Xor256Mix::new_from(1i64));
+        check_seeding(Lcg128Xor1024Mix::new_from(1i64));
+        // all-zero seeds get repaired
+        let mut prng = Lcg64Xor256Mix::from_seed([0u8; 48]);
+        assert_ne!(prng.next_long(), prng.next_long());
+        let mut prng = Lcg32Xor64Mix::from_seed([0u8; 16]);
+        assert_ne!(prng.next_long(), prng.next_long());
+    }
+
+    #[test]
+    fn test_advance() {
+        check_advance(Lcg32Xor64Mix::new_from(42i64));
+        check_advance(Lcg64Xor128Mix::new_from(42i64));
+        check_advance(Lcg64Xor128StarStar::new_from(42i64));
+        check_advance(Lcg64Xor256Mix::new_from(42i64));
+        check_advance(Lcg128Xor128Mix::new_from(42i64));
+        check_advance(Lcg128Xor256Mix::new_from(42i64));
+        check_advance(Lcg128Xor1024Mix::new_from(42i64));
+        // an advance by the period of xoroshiro64 only moves the LCG
+        let mut prng = Lcg32Xor64Mix::new_from(42i64);
+        let (x0, x1) = (prng.x0, prng.x1);
+        prng.advance_ints(u64::MAX as u128);
+        assert_eq!((prng.x0, prng.x1), (x0, x1));
+    }
+}
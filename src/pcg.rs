@@ -0,0 +1,598 @@
+// Copyright 2022 Stefan Zobel
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Melissa O'Neill's PCG family of generators: a linear congruential
+//! generator (LCG) whose state is scrambled by a permutation. The increment
+//! of the LCG selects one of 2<sup>63</sup> (or 2<sup>127</sup>) streams.
+
+use crate::jump_ahead::{lcg_advance, lcg_advance128};
+use crate::pseudo_random::{check_odd, expand_seed, seed_word, IllegalStateError};
+use crate::pseudo_random::{Advance, PseudoRandom, Seedable};
+use crate::split_mix64::SplitMix64;
+use crate::xor_shift_128plus::XorShift128Plus;
+use core::convert::TryInto;
+
+// the multiplier of the 64-bit LCG of Pcg32
+const M64: i64 = 0x5851f42d4c957f2du64 as i64;
+
+// the multiplier of the 128-bit LCG of Pcg64
+const M128: i128 = 0x2360ed051fc65da44385df649fccf645u128 as i128;
+
+// the "cheap" 64-bit multiplier of Pcg64Dxsm, used both in its 128-bit LCG
+// and in its output function
+const CHEAP_M: i64 = 0xda942042e4dd58b5u64 as i64;
+
+/*
+ * The constants of NumPy's SeedSequence, which is O'Neill's seed_seq_fe.
+ */
+const INIT_A: u32 = 0x43b0d7e5u32;
+const MULT_A: u32 = 0x931e8875u32;
+const INIT_B: u32 = 0x8b51f9ddu32;
+const MULT_B: u32 = 0x58f38dedu32;
+const MIX_MULT_L: u32 = 0xca01f9ddu32;
+const MIX_MULT_R: u32 = 0x4973f715u32;
+
+#[inline]
+fn seed_word128(seed: &[u8], i: usize) -> i128 {
+    i128::from_le_bytes(seed[8 * i..8 * i + 16].try_into().unwrap())
+}
+
+// Returns the (initstate, initseq) pair that NumPy's PCG64 derives from an
+// integer seed of at most 128 bits, i.e. SeedSequence(seed).generate_state(4,
+// np.uint64) with the first two words as the high and low half of initstate
+// and the last two words as the high and low half of initseq.
+fn numpy_seed(seed: u128) -> (i128, i128) {
+    let mut hash_const = INIT_A;
+    let mut hash_mix = |value: u32| {
+        let mut v = value ^ hash_const;
+        hash_const = hash_const.wrapping_mul(MULT_A);
+        v = v.wrapping_mul(hash_const);
+        v ^ (v >> 16)
+    };
+    let mix = |x: u32, y: u32| {
+        let r = MIX_MULT_L
+            .wrapping_mul(x)
+            .wrapping_sub(MIX_MULT_R.wrapping_mul(y));
+        r ^ (r >> 16)
+    };
+    // the seed's 32-bit words (least significant first) form the entropy
+    // which NumPy pads with zeros to the pool size of 4
+    let mut pool = [0u32; 4];
+    for (i, w) in pool.iter_mut().enumerate() {
+        *w = hash_mix((seed >> (32 * i)) as u32);
+    }
+    for src in 0..4 {
+        for dst in 0..4 {
+            if src != dst {
+                pool[dst] = mix(pool[dst], hash_mix(pool[src]));
+            }
+        }
+    }
+    let mut hash_const = INIT_B;
+    let mut words = [0u64; 4];
+    for (i, w) in words.iter_mut().enumerate() {
+        for half in 0..2 {
+            let mut v = pool[(2 * i + half) % 4] ^ hash_const;
+            hash_const = hash_const.wrapping_mul(MULT_B);
+            v = v.wrapping_mul(hash_const);
+            v ^= v >> 16;
+            *w |= (v as u64) << (32 * half);
+        }
+    }
+    let init_state = ((words[0] as u128) << 64) | words[1] as u128;
+    let init_seq = ((words[2] as u128) << 64) | words[3] as u128;
+    (init_state as i128, init_seq as i128)
+}
+
+/// 64-bit `PCG32` (XSH RR 64/32) pseudo random generator suggested by
+/// <a href=https://www.pcg-random.org/pdf/hmc-cs-2014-0905.pdf>Melissa O'Neill
+/// (2014)</a>. This is the `pcg32` generator of the PCG reference
+/// implementation in C, which outputs 32 bits per step of its LCG, so that
+/// [next_long()](PseudoRandom::next_long) combines two steps.
+///
+/// This generator has a period of 2<sup>64</sup> and 2<sup>63</sup> different
+/// streams.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pcg32 {
+    // the LCG state
+    s: i64,
+    // the increment of the LCG, must be odd
+    inc: i64,
+}
+
+impl PseudoRandom for Pcg32 {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        let hi = (self.next_int() as i64) << 32;
+        hi | (self.next_int() as u32 as i64)
+    }
+
+    #[inline]
+    fn next_int(&mut self) -> i32 {
+        let old = self.s as u64;
+        self.s = M64.wrapping_mul(self.s).wrapping_add(self.inc);
+        let xor_shifted = (((old >> 18) ^ old) >> 27) as u32;
+        xor_shifted.rotate_right((old >> 59) as u32) as i32
+    }
+}
+
+impl Pcg32 {
+    /// Creates a new [Pcg32](Pcg32) initialized with a random seed.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn new() -> Self {
+        Pcg32::internal_new(&mut XorShift128Plus::new())
+    }
+
+    /// Creates a new [Pcg32](Pcg32) initialized with the given `seed`.
+    #[inline]
+    pub fn new_from(seed: i64) -> Self {
+        Pcg32::internal_new(&mut XorShift128Plus::new_from(seed))
+    }
+
+    /// Creates a new [Pcg32](Pcg32) on the stream `stream` that is seeded
+    /// with `state` in the same way as by `pcg32_srandom_r(rng, state, stream)`
+    /// of the PCG reference implementation. The highest bit of `stream` is
+    /// ignored.
+    #[inline]
+    pub fn new_with_stream(state: i64, stream: i64) -> Self {
+        let mut instance = Pcg32 {
+            s: 0i64,
+            inc: (stream << 1) | 1i64,
+        };
+        instance.next_int();
+        instance.s = instance.s.wrapping_add(state);
+        instance.next_int();
+        instance
+    }
+
+    /// Creates a new [Pcg32](Pcg32) from its complete state: the LCG state
+    /// `state` and the increment `inc` (`state` and `inc` of the reference
+    /// implementation's `pcg32_random_t`).
+    ///
+    /// Returns an error if `inc` is even.
+    #[inline]
+    pub fn new_from_state(state: i64, inc: i64) -> Result<Self, IllegalStateError> {
+        check_odd(inc as i128)?;
+        Ok(Pcg32 { s: state, inc })
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        Pcg32::new_with_stream(seeder.next_long(), seeder.next_long())
+    }
+}
+
+impl Advance for Pcg32 {
+    /// Advances this generator by `n` calls to [next_long()](PseudoRandom::next_long),
+    /// i.e., by 2&nbsp;*&nbsp;`n` steps of its LCG or calls to
+    /// [next_int()](PseudoRandom::next_int).
+    #[inline]
+    fn advance(&mut self, n: u128) {
+        self.s = lcg_advance(self.s, M64, self.inc, n.wrapping_mul(2u128));
+    }
+}
+
+impl Seedable for Pcg32 {
+    type Seed = [u8; 16];
+
+    /// Uses the first 8 bytes as the `state` and the last 8 bytes as the
+    /// `stream` of [new_with_stream()](Pcg32::new_with_stream).
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Pcg32::new_with_stream(seed_word(&seed, 0), seed_word(&seed, 1))
+    }
+
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_seed(expand_seed(SplitMix64::new_from(state as i64)))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn from_entropy() -> Self {
+        Self::from_seed(crate::seed::system_seed())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for Pcg32 {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 128-bit `PCG64` (XSL RR 128/64) pseudo random generator suggested by
+/// <a href=https://www.pcg-random.org/pdf/hmc-cs-2014-0905.pdf>Melissa O'Neill
+/// (2014)</a>. This is the `pcg64` generator of the PCG reference
+/// implementation in C and the default generator of NumPy (`numpy.random.PCG64`).
+///
+/// This generator has a period of 2<sup>128</sup> and 2<sup>127</sup> different
+/// streams.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pcg64 {
+    // the LCG state
+    s: i128,
+    // the increment of the LCG, must be odd
+    inc: i128,
+}
+
+impl PseudoRandom for Pcg64 {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        self.s = M128.wrapping_mul(self.s).wrapping_add(self.inc);
+        let rot = (self.s as u128 >> 122) as u32;
+        (((self.s >> 64) as i64) ^ (self.s as i64)).rotate_right(rot)
+    }
+}
+
+impl Pcg64 {
+    /// Creates a new [Pcg64](Pcg64) initialized with a random seed.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn new() -> Self {
+        Pcg64::internal_new(&mut XorShift128Plus::new())
+    }
+
+    /// Creates a new [Pcg64](Pcg64) initialized with the given `seed`.
+    #[inline]
+    pub fn new_from(seed: i64) -> Self {
+        Pcg64::internal_new(&mut XorShift128Plus::new_from(seed))
+    }
+
+    /// Creates a new [Pcg64](Pcg64) that is seeded in the same way as NumPy's
+    /// `numpy.random.PCG64(seed)` (and hence `numpy.random.default_rng(seed)`)
+    /// for an integer `seed`, i.e., both produce the identical stream. This
+    /// includes the 128-bit `entropy` of a `numpy.random.SeedSequence`.
+    #[inline]
+    pub fn new_numpy(seed: u128) -> Self {
+        let (state, stream) = numpy_seed(seed);
+        Pcg64::new_with_stream(state, stream)
+    }
+
+    /// Creates a new [Pcg64](Pcg64) on the stream `stream` that is seeded
+    /// with `state` in the same way as by `pcg64_srandom_r(rng, state, stream)`
+    /// of the PCG reference implementation. The highest bit of `stream` is
+    /// ignored.
+    #[inline]
+    pub fn new_with_stream(state: i128, stream: i128) -> Self {
+        let mut instance = Pcg64 {
+            s: 0i128,
+            inc: (stream << 1) | 1i128,
+        };
+        instance.next_long();
+        instance.s = instance.s.wrapping_add(state);
+        instance.next_long();
+        instance
+    }
+
+    /// Creates a new [Pcg64](Pcg64) from its complete state: the LCG state
+    /// `state` and the increment `inc`. These are the values of
+    /// `bit_generator.state["state"]["state"]` and
+    /// `bit_generator.state["state"]["inc"]` of a NumPy `PCG64`.
+    ///
+    /// Returns an error if `inc` is even.
+    #[inline]
+    pub fn new_from_state(state: i128, inc: i128) -> Result<Self, IllegalStateError> {
+        check_odd(inc)?;
+        Ok(Pcg64 { s: state, inc })
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let state = join(seeder.next_long(), seeder.next_long());
+        let stream = join(seeder.next_long(), seeder.next_long());
+        Pcg64::new_with_stream(state, stream)
+    }
+}
+
+impl Advance for Pcg64 {
+    /// Advances this generator by `n` steps in the same way as NumPy's
+    /// `PCG64.advance(n)` does.
+    #[inline]
+    fn advance(&mut self, n: u128) {
+        self.s = lcg_advance128(self.s, M128, self.inc, n);
+    }
+}
+
+impl Seedable for Pcg64 {
+    type Seed = [u8; 32];
+
+    /// Uses the first 16 bytes as the `state` and the last 16 bytes as the
+    /// `stream` of [new_with_stream()](Pcg64::new_with_stream).
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Pcg64::new_with_stream(seed_word128(&seed, 0), seed_word128(&seed, 2))
+    }
+
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_seed(expand_seed(SplitMix64::new_from(state as i64)))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn from_entropy() -> Self {
+        Self::from_seed(crate::seed::system_seed())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for Pcg64 {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 128-bit `PCG64 DXSM` pseudo random generator, the variant of
+/// [Pcg64](Pcg64) that NumPy provides as `numpy.random.PCG64DXSM`. It uses a
+/// cheaper 64-bit multiplier for its LCG and the stronger "double xorshift
+/// multiply" output function on the state before the LCG step.
+///
+/// This generator has a period of 2<sup>128</sup> and 2<sup>127</sup> different
+/// streams.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pcg64Dxsm {
+    // the LCG state
+    s: i128,
+    // the increment of the LCG, must be odd
+    inc: i128,
+}
+
+impl PseudoRandom for Pcg64Dxsm {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        let mut hi = (self.s >> 64) as u64;
+        let lo = self.s as u64 | 1u64;
+        hi ^= hi >> 32;
+        hi = hi.wrapping_mul(CHEAP_M as u64);
+        hi ^= hi >> 48;
+        hi = hi.wrapping_mul(lo);
+        self.s = (CHEAP_M as u64 as i128)
+            .wrapping_mul(self.s)
+            .wrapping_add(self.inc);
+        hi as i64
+    }
+}
+
+impl Pcg64Dxsm {
+    /// Creates a new [Pcg64Dxsm](Pcg64Dxsm) initialized with a random seed.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn new() -> Self {
+        Pcg64Dxsm::internal_new(&mut XorShift128Plus::new())
+    }
+
+    /// Creates a new [Pcg64Dxsm](Pcg64Dxsm) initialized with the given `seed`.
+    #[inline]
+    pub fn new_from(seed: i64) -> Self {
+        Pcg64Dxsm::internal_new(&mut XorShift128Plus::new_from(seed))
+    }
+
+    /// Creates a new [Pcg64Dxsm](Pcg64Dxsm) that is seeded in the same way as
+    /// NumPy's `numpy.random.PCG64DXSM(seed)` for an integer `seed`, i.e., both
+    /// produce the identical stream. This includes the 128-bit `entropy` of a
+    /// `numpy.random.SeedSequence`.
+    #[inline]
+    pub fn new_numpy(seed: u128) -> Self {
+        let (state, stream) = numpy_seed(seed);
+        Pcg64Dxsm::new_with_stream(state, stream)
+    }
+
+    /// Creates a new [Pcg64Dxsm](Pcg64Dxsm) on the stream `stream` that is
+    /// seeded with `state` in the same way as NumPy's `PCG64DXSM` seeds its
+    /// LCG. The highest bit of `stream` is ignored.
+    #[inline]
+    pub fn new_with_stream(state: i128, stream: i128) -> Self {
+        let mut instance = Pcg64Dxsm {
+            s: 0i128,
+            inc: (stream << 1) | 1i128,
+        };
+        instance.next_long();
+        instance.s = instance.s.wrapping_add(state);
+        instance.next_long();
+        instance
+    }
+
+    /// Creates a new [Pcg64Dxsm](Pcg64Dxsm) from its complete state: the LCG
+    /// state `state` and the increment `inc`. These are the values of
+    /// `bit_generator.state["state"]["state"]` and
+    /// `bit_generator.state["state"]["inc"]` of a NumPy `PCG64DXSM`.
+    ///
+    /// Returns an error if `inc` is even.
+    #[inline]
+    pub fn new_from_state(state: i128, inc: i128) -> Result<Self, IllegalStateError> {
+        check_odd(inc)?;
+        Ok(Pcg64Dxsm { s: state, inc })
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let state = join(seeder.next_long(), seeder.next_long());
+        let stream = join(seeder.next_long(), seeder.next_long());
+        Pcg64Dxsm::new_with_stream(state, stream)
+    }
+}
+
+impl Advance for Pcg64Dxsm {
+    /// Advances this generator by `n` steps in the same way as NumPy's
+    /// `PCG64DXSM.advance(n)` does.
+    #[inline]
+    fn advance(&mut self, n: u128) {
+        self.s = lcg_advance128(self.s, CHEAP_M as u64 as i128, self.inc, n);
+    }
+}
+
+impl Seedable for Pcg64Dxsm {
+    type Seed = [u8; 32];
+
+    /// Uses the first 16 bytes as the `state` and the last 16 bytes as the
+    /// `stream` of [new_with_stream()](Pcg64Dxsm::new_with_stream).
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Pcg64Dxsm::new_with_stream(seed_word128(&seed, 0), seed_word128(&seed, 2))
+    }
+
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_seed(expand_seed(SplitMix64::new_from(state as i64)))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn from_entropy() -> Self {
+        Self::from_seed(crate::seed::system_seed())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for Pcg64Dxsm {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[inline]
+fn join(high: i64, low: i64) -> i128 {
+    ((high as i128) << 64) | (low as u64 as i128)
+}
+
+#[cfg(test)]
+mod pcg_tests {
+    use super::*;
+    use crate::test_util::check_advance;
+
+    // The expected values of new_with_stream(42, 54) are the outputs of the
+    // pcg32-demo and pcg64-demo programs of the PCG reference implementation
+    // in C, those of Pcg64Dxsm have been produced by a C implementation of
+    // NumPy's pcg64_dxsm. NumPy's default_rng(12345).random(3) returns
+    // [0.22733602246716966, 0.31675833970975287, 0.7973654573327341].
+
+    #[test]
+    fn test_pcg32() {
+        let mut prng = Pcg32::new_with_stream(42i64, 54i64);
+        for &e in &[
+            0xa15c02b7u32,
+            0x7b47f409u32,
+            0xba1d3330u32,
+            0x83d2f293u32,
+            0xbfa4784bu32,
+            0xcbed606eu32,
+        ] {
+            assert_eq!(prng.next_int(), e as i32);
+        }
+        let mut prng = Pcg32::new_with_stream(42i64, 54i64);
+        assert_eq!(prng.next_long(), 0xa15c02b77b47f409u64 as i64);
+        assert_eq!(prng.next_long(), 0xba1d333083d2f293u64 as i64);
+    }
+
+    #[test]
+    fn test_pcg64() {
+        let mut prng = Pcg64::new_with_stream(42i128, 54i128);
+        for &e in &[
+            0x86b1da1d72062b68u64,
+            0x1304aa46c9853d39u64,
+            0xa3670e9e0dd50358u64,
+            0xf9090e529a7dae00u64,
+            0xc85b9fd837996f2cu64,
+            0x606121f8e3919196u64,
+        ] {
+            assert_eq!(prng.next_long(), e as i64);
+        }
+    }
+
+    #[test]
+    fn test_pcg64_dxsm() {
+        let mut prng = Pcg64Dxsm::new_with_stream(42i128, 54i128);
+        for &e in &[
+            0xf0847c9518bddb90u64,
+            0x8e7d5f5514ba8aaau64,
+            0x86fbd36f8028f6fdu64,
+            0x8d14b6edbe9f740au64,
+            0xa85b2896c7cad55du64,
+            0x8ca3894a1d9227bbu64,
+        ] {
+            assert_eq!(prng.next_long(), e as i64);
+        }
+    }
+
+    #[test]
+    fn test_new_numpy() {
+        let mut prng = Pcg64::new_numpy(12345u128);
+        assert_eq!(prng.next_double(), 0.22733602246716966f64);
+        assert_eq!(prng.next_double(), 0.31675833970975287f64);
+        assert_eq!(prng.next_double(), 0.7973654573327341f64);
+        let mut prng = Pcg64::new_numpy(12345u128);
+        assert_eq!(prng.next_long(), 0x3a32b18db2ffc19du64 as i64);
+        let mut prng = Pcg64Dxsm::new_numpy(12345u128);
+        assert_eq!(prng.next_long(), 0xee1724741eab7970u64 as i64);
+        assert_eq!(prng.next_long(), 0x6c2087d9f61c7316u64 as i64);
+        assert_eq!(prng.next_long(), 0x98e72a2e51afc766u64 as i64);
+    }
+
+    #[test]
+    fn test_new_from_state() {
+        let mut prng1 = Pcg64::new_from_state(
+            0x1905e0335aae96349199b0d09775add5u128 as i128,
+            0xc9c7353e6e2b1f287d761f2d4027fae7u128 as i128,
+        )
+        .unwrap();
+        let mut prng2 = Pcg64::new_numpy(12345u128);
+        for _ in 0..16 {
+            assert_eq!(prng1.next_long(), prng2.next_long());
+        }
+        let mut prng1 =
+            Pcg32::new_from_state(0x8c89cf5f619b20f1u64 as i64, 0xfdb97530eca86421u64 as i64)
+                .unwrap();
+        let mut prng2 = Pcg32::new_with_stream(0x0123456789abcdefi64, 0xfedcba9876543210u64 as i64);
+        for _ in 0..16 {
+            assert_eq!(prng1.next_long(), prng2.next_long());
+        }
+        assert_eq!(
+            Pcg32::new_from_state(1i64, 2i64).unwrap_err(),
+            IllegalStateError::EvenIncrement
+        );
+        assert_eq!(
+            Pcg64::new_from_state(1i128, 2i128).unwrap_err(),
+            IllegalStateError::EvenIncrement
+        );
+        assert_eq!(
+            Pcg64Dxsm::new_from_state(1i128, 2i128).unwrap_err(),
+            IllegalStateError::EvenIncrement
+        );
+    }
+
+    #[test]
+    fn test_streams() {
+        // the same state on different streams gives different sequences
+        let mut prng1 = Pcg64::new_with_stream(42i128, 54i128);
+        let mut prng2 = Pcg64::new_with_stream(42i128, 55i128);
+        assert_ne!(prng1.next_long(), prng2.next_long());
+        let mut prng1 = Pcg32::new_with_stream(42i64, 54i64);
+        let mut prng2 = Pcg32::new_with_stream(42i64, 55i64);
+        assert_ne!(prng1.next_long(), prng2.next_long());
+    }
+
+    #[test]
+    fn test_advance() {
+        check_advance(Pcg32::new_from(42i64));
+        check_advance(Pcg64::new_from(42i64));
+        check_advance(Pcg64Dxsm::new_from(42i64));
+        // a full period of Pcg32's LCG (2^63 calls to next_long()) ends up
+        // at the start again
+        let mut advanced = Pcg32::new_from(42i64);
+        let mut prng = advanced.clone();
+        advanced.advance(1u128 << 63);
+        assert_eq!(advanced.next_long(), prng.next_long());
+    }
+}
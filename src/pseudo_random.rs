@@ -11,10 +11,20 @@
 //!
 //! The default algorithm which is used in the thread-local generator [ThreadLocalPrng](ThreadLocalPrng) is
 //! <a href=https://github.com/tylov/STC/blob/master/docs/crandom_api.md>Tyge Løvset's stc64 generator</a>
-//! which is implemented in [Stc64](Stc64).
+//! which is implemented in [Stc64](Stc64). Every other generator of this module can be used
+//! as a thread-local generator through [ThreadLocal](ThreadLocal), e.g. `ThreadLocal<Lcg64Xor1024Mix>`.
 //!
 //! Another fast high quality algorithm is <a href=https://arxiv.org/pdf/1805.01407.pdf>Blackman and Vigna's (2019) xoshiro256**</a>
-//! which is provided by [XoShiRo256StarStar](XoShiRo256StarStar).
+//! which is provided by [XoShiRo256StarStar](XoShiRo256StarStar). The other members of the
+//! family trade state size against period: [Xoroshiro128PlusPlus](Xoroshiro128PlusPlus) and
+//! [Xoroshiro128StarStar](Xoroshiro128StarStar) for per-particle generators,
+//! [Xoshiro256PlusPlus](Xoshiro256PlusPlus) and [Xoshiro256Plus](Xoshiro256Plus) (for fast
+//! floating point values) with the same state size as xoshiro256**, and
+//! [Xoshiro512StarStar](Xoshiro512StarStar) and [Xoroshiro1024StarStar](Xoroshiro1024StarStar)
+//! for very long simulations. All of them provide `jump()` and `long_jump()`.
+//!
+//! Where many small independent generators are needed, [SplitMix64](SplitMix64) with only
+//! 128 bits of state can be split in the same way as Java's `SplittableRandom`.
 //!
 //! For applications that use tuples of consecutively generated values, it may be desirable
 //! to use a generator that is k-dimensionally equidistributed such that k is at least as
@@ -25,18 +35,124 @@
 //! period (2<sup>64</sup>(2<sup>1024</sup>&minus;1)) and state space (1088 bits) than the
 //! other generators and is about 3 to 4 times slower than [Stc64](Stc64).
 //!
+//! The other members of JDK 17's LXM family are available as well, so that state size,
+//! speed and equidistribution can be traded off against each other in the same way as in
+//! Java:
+//!
+//! | Rust                                       | Java                    | state bits | equidistribution |
+//! |--------------------------------------------|-------------------------|-----------:|-----------------:|
+//! | [Lcg32Xor64Mix](Lcg32Xor64Mix)             | `L32X64MixRandom`       |         96 |                1 |
+//! | [Lcg64Xor128Mix](Lcg64Xor128Mix)           | `L64X128MixRandom`      |        192 |                2 |
+//! | [Lcg64Xor128StarStar](Lcg64Xor128StarStar) | `L64X128StarStarRandom` |        192 |                2 |
+//! | [Lcg64Xor256Mix](Lcg64Xor256Mix)           | `L64X256MixRandom`      |        320 |                4 |
+//! | [Lcg64Xor1024Mix](Lcg64Xor1024Mix)         | `L64X1024MixRandom`     |       1088 |               16 |
+//! | [Lcg128Xor128Mix](Lcg128Xor128Mix)         | `L128X128MixRandom`     |        256 |                1 |
+//! | [Lcg128Xor256Mix](Lcg128Xor256Mix)         | `L128X256MixRandom`     |        384 |                1 |
+//! | [Lcg128Xor1024Mix](Lcg128Xor1024Mix)       | `L128X1024MixRandom`    |       1152 |                1 |
+//!
+//! Melissa O'Neill's <a href=https://www.pcg-random.org>PCG family</a> is provided by
+//! [Pcg32](Pcg32), [Pcg64](Pcg64) and [Pcg64Dxsm](Pcg64Dxsm). Their streams are selected by the
+//! increment of the underlying LCG (see `new_with_stream()`) and they can be advanced by any
+//! number of steps in O(log n) time through [Advance](Advance).
+//!
+//! The counter-based generators [Philox4x32](Philox4x32), [Philox4x64](Philox4x64) and
+//! [Threefry2x64](Threefry2x64) of the <a href=https://github.com/DEShawResearch/random123>Random123</a>
+//! library compute each block of random numbers directly from a key and a counter. A
+//! generator positioned by `set_counter()` (or the stateless `block()` function) gives the
+//! random numbers for, e.g., particle i at time step t independently of the order in
+//! which the particles are processed.
+//!
 //! All of these algorithms have good performance in statistical tests and so far no major issues
-//! are known. **None** of them is cryptographically secure. A weakness of the current implementation
-//! is that all of them can only be seeded by a single `i64` which is theoretically insufficient
-//! for the state space these generators have. However, this should hardly be detectable in actual
-//! simulations.
+//! are known. **None** of them is cryptographically secure. Note that seeding by a single `i64`
+//! (as `new_from()` does) is theoretically insufficient for the state space these generators
+//! have. However, this should hardly be detectable in actual simulations. Where the complete
+//! state space must be reachable (or a published reference stream must be reproduced) each
+//! generator can also be constructed from its full state by `new_from_state()`.
+//!
+//! # Compatibility with Java
+//!
+//! The following generators produce exactly the same stream as their Java counterparts
+//! when they are given the same `long` seed:
+//!
+//! | Rust                                                               | Java                       |
+//! |--------------------------------------------------------------------|----------------------------|
+//! | [Lcg64Xor1024Mix::new_java()](Lcg64Xor1024Mix::new_java)           | `L64X1024MixRandom(long)`  |
+//! | [Xoshiro256PlusPlus::new_java()](Xoshiro256PlusPlus::new_java)     | `Xoshiro256PlusPlus(long)` |
+//! | [JavaSplittableRandom::new_from()](JavaSplittableRandom::new_from) | `SplittableRandom(long)`   |
+//! | [JavaRandom::new_from()](JavaRandom::new_from)                     | `java.util.Random(long)`   |
+//!
+//! The bounded methods [next_long_up_to()](PseudoRandom::next_long_up_to),
+//! [next_long_from_range()](PseudoRandom::next_long_from_range) and
+//! [next_double_from_range()](PseudoRandom::next_double_from_range) use the same algorithms as
+//! JDK 17's `RandomGenerator.nextLong(bound)`, `nextLong(origin, bound)` and
+//! `nextDouble(origin, bound)`, so that they also return the same values as in Java.
+//!
+//! # Compatibility with NumPy
+//!
+//! [Pcg64::new_numpy()](Pcg64::new_numpy) and [Pcg64Dxsm::new_numpy()](Pcg64Dxsm::new_numpy)
+//! produce exactly the same stream as NumPy's `PCG64(seed)` (which is what
+//! `numpy.random.default_rng(seed)` uses) and `PCG64DXSM(seed)` for the same integer `seed`.
+//! [next_long()](PseudoRandom::next_long) corresponds to their `random_raw()` and
+//! [next_double()](PseudoRandom::next_double) to `Generator.random()`. A NumPy generator's
+//! current state can be transferred by `new_from_state()`.
+//!
+//! # `no_std` support
+//!
+//! Without the default `std` feature the crate is `no_std` (it still needs `alloc`).
+//! All generators are available then, but they have to be seeded explicitly by `new_from()`,
+//! `new_from_state()` or the [Seedable](Seedable) trait. Seeding from the system (`new()`,
+//! `Default` and [from_entropy()](Seedable::from_entropy)), [ThreadLocalPrng](ThreadLocalPrng)
+//! and [next_gaussians()](PseudoRandom::next_gaussians) require the `std` feature.
+//!
+//! # Binary state format
+//!
+//! The generators' `to_bytes()` and `from_bytes()` methods use a compact binary format
+//! that doesn't depend on any serialization framework. Its layout (version 1) is
+//!
+//! | bytes            | content                                                   |
+//! |------------------|-----------------------------------------------------------|
+//! | 0                | format version (`1`)                                      |
+//! | 1                | algorithm tag                                             |
+//! | 2 .. n&minus;4   | state words as little-endian 64-bit integers              |
+//! | n&minus;4 .. n   | little-endian CRC-32 (IEEE 802.3) of bytes 0 .. n&minus;4 |
+//!
+//! where the algorithm tag is `1` for [Stc64](Stc64) (state `s0` to `s3`, `seq`), `2` for
+//! [XoShiRo256StarStar](XoShiRo256StarStar) (state `s[0]` to `s[3]`) and `3` for
+//! [Lcg64Xor1024Mix](Lcg64Xor1024Mix) (`a`, `s`, then the 16 xoroshiro1024 words rotated
+//! such that the next call to `next_long()` reads index 15 and 0 first). This format is
+//! guaranteed to stay stable across releases of this crate: an incompatible change would
+//! have to use a new format version and the existing versions remain readable.
+//!
+//! Only these three generators have a binary state format. The state of the other
+//! generators can be saved and restored with the optional `serde` feature.
 //!
 
-use crate::bit_mix::lea_mix64;
-use crate::seed::black_hole;
+use crate::bit_mix::{lea_mix64, murmur_mix64, stafford_mix13};
+use crate::jump_ahead::{lcg_advance, x_pow_mod};
+use crate::split_mix64_seed::{GOLDEN, SILVER};
+use crate::state_format::{StateReader, StateWriter};
+use crate::state_format::{TAG_LCG64_XOR1024_MIX, TAG_STC64, TAG_XOSHIRO256_STAR_STAR};
 use crate::xor_shift_128plus::XorShift128Plus;
-use core::cell::UnsafeCell;
-use core::ptr::NonNull;
+use crate::xoshiro::{XOROSHIRO1024_CHAR_POLY, XOSHIRO256_CHAR_POLY};
+use crate::xoshiro::{XOSHIRO256_JUMP, XOSHIRO256_LONG_JUMP};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::fmt;
+
+pub use crate::counter_based::{Philox4x32, Philox4x64, Threefry2x64};
+pub use crate::java::{JavaRandom, JavaSplittableRandom};
+pub use crate::lxm::{Lcg128Xor1024Mix, Lcg128Xor128Mix, Lcg128Xor256Mix};
+pub use crate::lxm::{Lcg32Xor64Mix, Lcg64Xor128Mix, Lcg64Xor128StarStar, Lcg64Xor256Mix};
+pub use crate::pcg::{Pcg32, Pcg64, Pcg64Dxsm};
+#[cfg(feature = "rand_core")]
+pub use crate::rand_compat::{RngCoreAdapter, SeedBytes};
+pub use crate::split_mix64::SplitMix64;
+pub use crate::state_format::StateFormatError;
+#[cfg(feature = "std")]
+pub use crate::thread_local_prng::{ThreadLocal, ThreadLocalGenerator, ThreadLocalPrng};
+pub use crate::xoshiro::{Xoroshiro1024StarStar, Xoroshiro128PlusPlus, Xoroshiro128StarStar};
+pub use crate::xoshiro::{Xoshiro256Plus, Xoshiro256PlusPlus, Xoshiro512StarStar};
 
 const DOUBLE_NORM: f64 = 1.0f64 / (1i64 << 53) as f64;
 const FLOAT_NORM: f32 = 1.0f32 / (1i32 << 24) as f32;
@@ -81,6 +197,8 @@ pub trait PseudoRandom {
     /// where `n` is the **strictly positive** bound on the random number to be returned.
     /// This method panics when `n` is `<= 0`.
     ///
+    /// The algorithm is the same as in JDK 17's `RandomGenerator.nextLong(bound)`.
+    ///
     /// # Panics
     ///
     /// Panics if `n` is zero or negative.
@@ -99,7 +217,8 @@ pub trait PseudoRandom {
         let mut y = (x as u64 >> 1) as i64;
         loop {
             x = y % n;
-            if y + n_minus1 - x < 0i64 {
+            // reject the candidate if y + n_minus1 - x overflows
+            if y.wrapping_add(n_minus1).wrapping_sub(x) >= 0i64 {
                 break;
             }
             y = (self.next_long() as u64 >> 1) as i64;
@@ -152,6 +271,67 @@ pub trait PseudoRandom {
         min + (max - min) * self.next_float()
     }
 
+    /// Returns a signed 64-bit integer which is uniformly distributed in the half-open
+    /// range [origin, bound). The algorithm is the same as in JDK 17's
+    /// `RandomGenerator.nextLong(origin, bound)` and also works for ranges whose
+    /// length doesn't fit into an `i64`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bound <= origin`.
+    #[inline]
+    fn next_long_from_range(&mut self, origin: i64, bound: i64) -> i64 {
+        if bound <= origin {
+            panic!("bound must be greater than origin");
+        }
+        let mut x = self.next_long();
+        let n = bound.wrapping_sub(origin);
+        let n_minus1 = n.wrapping_sub(1i64);
+        if (n & n_minus1) == 0i64 {
+            // power of two shortcut
+            return (x & n_minus1).wrapping_add(origin);
+        }
+        if n > 0i64 {
+            // rejection-based algorithm to get uniform longs
+            let mut y = (x as u64 >> 1) as i64;
+            loop {
+                x = y % n;
+                if y.wrapping_add(n_minus1).wrapping_sub(x) >= 0i64 {
+                    break;
+                }
+                y = (self.next_long() as u64 >> 1) as i64;
+            }
+            return x + origin;
+        }
+        // the range isn't representable as an i64
+        while x < origin || x >= bound {
+            x = self.next_long();
+        }
+        x
+    }
+
+    /// Returns a 64-bit floating point value which is uniformly distributed in the half-open
+    /// range [origin, bound). Unlike [next_double_from_interval()](Self::next_double_from_interval)
+    /// this never returns `bound` due to rounding. The algorithm is the same as in JDK 17's
+    /// `RandomGenerator.nextDouble(origin, bound)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bound <= origin` or if `bound - origin` is not finite.
+    #[inline]
+    fn next_double_from_range(&mut self, origin: f64, bound: f64) -> f64 {
+        if !(origin < bound && (bound - origin) < f64::INFINITY) {
+            panic!("bound must be greater than origin and the range must be finite");
+        }
+        let r = self.next_double() * (bound - origin) + origin;
+        if r >= bound {
+            // correct the rounding to the largest value below bound
+            next_down(bound)
+        } else {
+            r
+        }
+    }
+
     /// Generates random bytes and places them into the user-supplied `bytes` slice.
     /// The number of random bytes produced is equal to the length of the slice.
     #[inline]
@@ -198,6 +378,9 @@ pub trait PseudoRandom {
     /// Generates two standard normal distributed 64-bit floating point values and stores
     /// them into the user-supplied `out` array. Here, "standard normal" means `N(0, 1)`, i.e.
     /// a normal distribution with expectation `0` and variance `1`.
+    ///
+    /// This method requires the `std` feature.
+    #[cfg(feature = "std")]
     #[inline]
     fn next_gaussians(&mut self, out: &mut [f64; 2]) {
         // Marsaglia's polar method
@@ -218,7 +401,20 @@ pub trait PseudoRandom {
     }
 }
 
+// the largest f64 that is smaller than the finite value v
+#[inline]
+fn next_down(v: f64) -> f64 {
+    if v == 0.0f64 {
+        -f64::from_bits(1u64)
+    } else if v > 0.0f64 {
+        f64::from_bits(v.to_bits() - 1u64)
+    } else {
+        f64::from_bits(v.to_bits() + 1u64)
+    }
+}
+
 /// Implement `PseudoRandom` for references to a `PseudoRandom`.
+#[allow(clippy::needless_lifetimes)]
 impl<'a, R: PseudoRandom + ?Sized> PseudoRandom for &'a mut R {
     #[inline(always)]
     fn next_long(&mut self) -> i64 {
@@ -234,6 +430,186 @@ impl<R: PseudoRandom + ?Sized> PseudoRandom for Box<R> {
     }
 }
 
+/// A generator that can be instantiated from a byte array seed whose size
+/// matches the size of its state. This is modelled after the `SeedableRng`
+/// trait of the `rand_core` crate.
+///
+/// Together with [PseudoRandom](PseudoRandom) this allows to write generic
+/// code that is able to create the generators it works with.
+pub trait Seedable: Sized {
+    /// The seed type, a byte array that has the same size as the state
+    /// of the generator.
+    type Seed: Sized + AsMut<[u8]>;
+
+    /// Creates a new generator from the given `seed`. The seed is interpreted
+    /// as a sequence of little-endian integers (of the sizes of the state's
+    /// components, usually 64-bit) which are taken in the same order as by the
+    /// `new_from_state()` constructor of the generator.
+    ///
+    /// Contrary to `new_from_state()` this method never fails. A seed that
+    /// would result in an illegal state gets repaired instead: an even additive
+    /// constant is made odd and an all-zero state is replaced by the state that
+    /// [seed_from_u64(0)](Self::seed_from_u64) produces.
+    fn from_seed(seed: Self::Seed) -> Self;
+
+    /// Creates a new generator from a `Seed` that is expanded from the given
+    /// `u64` by a SplitMix64 generator. Different `state` values result in
+    /// different (and well-mixed) seeds.
+    fn seed_from_u64(state: u64) -> Self;
+
+    /// Creates a new generator from a `Seed` whose bytes are all taken from the
+    /// operating system's entropy source (see [entropy_seed()](crate::entropy_seed)),
+    /// unless the deterministic mode of [set_global_seed()](crate::set_global_seed)
+    /// is enabled.
+    #[cfg(feature = "std")]
+    fn from_entropy() -> Self;
+}
+
+/// A generator that can be advanced by an arbitrary number of steps without
+/// generating the skipped outputs. The cost of an advance grows only with
+/// the logarithm of the distance (and with the size of the state).
+///
+/// This allows to resume a simulation at a given draw index or to partition
+/// a single stream into deterministic blocks. Note that [Stc64](Stc64) can't
+/// implement this trait as its state transition is nonlinear.
+pub trait Advance {
+    /// Advances this generator by `n` steps, which is equivalent to (but much
+    /// faster than) `n` calls to [next_long()](PseudoRandom::next_long).
+    fn advance(&mut self, n: u128);
+}
+
+/// A generator that can be split into two generators, this one and a new one
+/// which is returned, such that both produce statistically independent streams.
+/// This is modelled after Java's `SplittableGenerator` and is useful for
+/// fork/join style parallel computations where each task needs its own
+/// generator.
+pub trait Splittable: PseudoRandom + Sized {
+    /// Returns a new generator split off from this one. The state of this
+    /// generator gets advanced in the process.
+    fn split(&mut self) -> Self;
+
+    /// Returns an iterator over `n` new generators split off from this one.
+    #[inline]
+    fn splits(&mut self, n: usize) -> Splits<'_, Self> {
+        Splits {
+            parent: self,
+            remaining: n,
+        }
+    }
+}
+
+/// An iterator over generators that get split off from a parent generator.
+///
+/// This `struct` is created by the [splits()](Splittable::splits) method of
+/// [Splittable](Splittable).
+#[derive(Debug)]
+pub struct Splits<'a, G: Splittable> {
+    parent: &'a mut G,
+    remaining: usize,
+}
+
+impl<G: Splittable> Iterator for Splits<'_, G> {
+    type Item = G;
+
+    #[inline]
+    fn next(&mut self) -> Option<G> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        Some(self.parent.split())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<G: Splittable> ExactSizeIterator for Splits<'_, G> {}
+
+#[inline]
+pub(crate) fn expand_seed<const N: usize>(mut rng: SplitMix64) -> [u8; N] {
+    let mut seed = [0u8; N];
+    for chunk in seed.chunks_mut(8) {
+        let len = chunk.len();
+        chunk.copy_from_slice(&rng.next_long().to_le_bytes()[..len]);
+    }
+    seed
+}
+
+static mut UNUSED: u8 = 0u8;
+
+#[inline]
+pub(crate) fn black_hole(val: u8) {
+    // SAFETY: this is safe because we really don't care what gets
+    // written into the UNUSED location either by a single thread
+    // or concurrently by multiple threads, neither will we ever
+    // attempt to read that memory location
+    unsafe {
+        UNUSED = val;
+    }
+}
+
+// the state words that the jdk.random generators derive from a single long seed
+#[inline]
+pub(crate) fn java_seed_words<const N: usize>(seed: i64) -> [i64; N] {
+    let mut v = seed ^ SILVER;
+    let mut x = [0i64; N];
+    for w in x.iter_mut() {
+        *w = stafford_mix13(v);
+        v = v.wrapping_add(GOLDEN);
+    }
+    x
+}
+
+#[inline]
+pub(crate) fn seed_word(seed: &[u8], i: usize) -> i64 {
+    i64::from_le_bytes(seed[8 * i..8 * i + 8].try_into().unwrap())
+}
+
+/// The error returned when a generator gets constructed from a state that
+/// is illegal for its algorithm.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IllegalStateError {
+    /// All state words that must contain at least one nonzero bit are zero.
+    AllZero,
+    /// An additive constant (or increment) that must be odd is even.
+    EvenIncrement,
+}
+
+impl fmt::Display for IllegalStateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IllegalStateError::AllZero => f.write_str("state must not be all zero"),
+            IllegalStateError::EvenIncrement => f.write_str("additive constant must be odd"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for IllegalStateError {}
+
+/// Returns an error if the additive constant `inc` is even.
+#[inline]
+pub(crate) fn check_odd(inc: i128) -> Result<(), IllegalStateError> {
+    if inc & 1i128 == 0i128 {
+        Err(IllegalStateError::EvenIncrement)
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns an error if all words of `x` are zero.
+#[inline]
+pub(crate) fn check_nonzero(x: &[i64]) -> Result<(), IllegalStateError> {
+    if x.iter().all(|&w| w == 0i64) {
+        Err(IllegalStateError::AllZero)
+    } else {
+        Ok(())
+    }
+}
+
 /// The 256-bit generator `Stc64` is Tyge Løvset's improved variation of
 /// `Sfc64`. See
 /// <https://github.com/tylov/STC/blob/master/include/stc/crandom.h>.
@@ -243,6 +619,7 @@ impl<R: PseudoRandom + ?Sized> PseudoRandom for Box<R> {
 ///
 /// This is the fastest generator supplied in this crate.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Stc64 {
     s0: i64,
     s1: i64,
@@ -270,6 +647,7 @@ impl PseudoRandom for Stc64 {
 
 impl Stc64 {
     /// Creates a new [Stc64](Stc64) initialized with a random seed.
+    #[cfg(feature = "std")]
     #[inline]
     pub fn new() -> Self {
         Stc64::internal_new(XorShift128Plus::new().next_long())
@@ -281,6 +659,41 @@ impl Stc64 {
         Stc64::internal_new(XorShift128Plus::new_from(seed).next_long())
     }
 
+    /// Creates a new [Stc64](Stc64) from its complete state, i.e., the four
+    /// state words and the stream increment `seq`.
+    ///
+    /// Returns an error if `seq` is even.
+    #[inline]
+    pub fn new_from_state(state: [i64; 4], seq: i64) -> Result<Self, IllegalStateError> {
+        check_odd(seq as i128)?;
+        Ok(Stc64 {
+            s0: state[0],
+            s1: state[1],
+            s2: state[2],
+            s3: state[3],
+            seq,
+        })
+    }
+
+    /// Returns the state of this generator in the stable, versioned
+    /// [binary state format](self#binary-state-format).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new(TAG_STC64, 40);
+        for &x in &[self.s0, self.s1, self.s2, self.s3, self.seq] {
+            writer.put_i64(x);
+        }
+        writer.finish()
+    }
+
+    /// Creates a new [Stc64](Stc64) from a state in the
+    /// [binary state format](self#binary-state-format) which has been produced
+    /// by [to_bytes()](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StateFormatError> {
+        let mut reader = StateReader::open(bytes, TAG_STC64, 40)?;
+        let state = [reader.i64(), reader.i64(), reader.i64(), reader.i64()];
+        Ok(Stc64::new_from_state(state, reader.i64())?)
+    }
+
     #[inline]
     fn internal_new(seed: i64) -> Self {
         let mut instance = Stc64 {
@@ -307,6 +720,54 @@ impl Stc64 {
     }
 }
 
+impl Splittable for Stc64 {
+    /// Returns a new [Stc64](Stc64) whose state words and whose `seq` increment
+    /// are drawn from this generator. Since generators with distinct `seq`
+    /// values add distinct Weyl sequences to their state the streams of the
+    /// parent and of the child are independent even if their states should
+    /// happen to be close.
+    #[inline]
+    fn split(&mut self) -> Self {
+        let seq = (self.next_long() << 1) | 1i64;
+        let mut child = Stc64 {
+            s0: self.next_long(),
+            s1: self.next_long(),
+            s2: self.next_long(),
+            s3: self.next_long(),
+            seq,
+        };
+        child.escape();
+        child
+    }
+}
+
+impl Seedable for Stc64 {
+    type Seed = [u8; 40];
+
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Stc64 {
+            s0: seed_word(&seed, 0),
+            s1: seed_word(&seed, 1),
+            s2: seed_word(&seed, 2),
+            s3: seed_word(&seed, 3),
+            seq: seed_word(&seed, 4) | 1i64,
+        }
+    }
+
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_seed(expand_seed(SplitMix64::new_from(state as i64)))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn from_entropy() -> Self {
+        Self::from_seed(crate::seed::system_seed())
+    }
+}
+
+#[cfg(feature = "std")]
 impl Default for Stc64 {
     #[inline]
     fn default() -> Self {
@@ -325,6 +786,7 @@ impl Default for Stc64 {
 ///
 /// This generator is almost as fast as [Stc64](Stc64).
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct XoShiRo256StarStar {
     x0: i64,
     x1: i64,
@@ -356,6 +818,7 @@ impl PseudoRandom for XoShiRo256StarStar {
 
 impl XoShiRo256StarStar {
     /// Creates a new [XoShiRo256StarStar](XoShiRo256StarStar) initialized with a random seed.
+    #[cfg(feature = "std")]
     #[inline]
     pub fn new() -> Self {
         XoShiRo256StarStar::internal_new(&mut XorShift128Plus::new())
@@ -367,6 +830,95 @@ impl XoShiRo256StarStar {
         XoShiRo256StarStar::internal_new(&mut XorShift128Plus::new_from(seed))
     }
 
+    /// Creates a new [XoShiRo256StarStar](XoShiRo256StarStar) from its complete
+    /// state `s[0]` to `s[3]` (in the notation of the reference implementation).
+    ///
+    /// Returns an error if all four state words are zero.
+    #[inline]
+    pub fn new_from_state(state: [i64; 4]) -> Result<Self, IllegalStateError> {
+        check_nonzero(&state)?;
+        Ok(XoShiRo256StarStar {
+            x0: state[0],
+            x1: state[1],
+            x2: state[2],
+            x3: state[3],
+        })
+    }
+
+    /// Returns the state of this generator in the stable, versioned
+    /// [binary state format](self#binary-state-format).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new(TAG_XOSHIRO256_STAR_STAR, 32);
+        for &x in &[self.x0, self.x1, self.x2, self.x3] {
+            writer.put_i64(x);
+        }
+        writer.finish()
+    }
+
+    /// Creates a new [XoShiRo256StarStar](XoShiRo256StarStar) from a state in the
+    /// [binary state format](self#binary-state-format) which has been produced
+    /// by [to_bytes()](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StateFormatError> {
+        let mut reader = StateReader::open(bytes, TAG_XOSHIRO256_STAR_STAR, 32)?;
+        let state = [reader.i64(), reader.i64(), reader.i64(), reader.i64()];
+        Ok(XoShiRo256StarStar::new_from_state(state)?)
+    }
+
+    /// Advances this generator by 2<sup>128</sup> steps, which is equivalent
+    /// to 2<sup>128</sup> calls to [next_long()](PseudoRandom::next_long). This
+    /// can be used to generate 2<sup>128</sup> non-overlapping subsequences for
+    /// parallel computations.
+    #[inline]
+    pub fn jump(&mut self) {
+        self.jump_by(&XOSHIRO256_JUMP);
+    }
+
+    /// Advances this generator by 2<sup>192</sup> steps, which is equivalent
+    /// to 2<sup>192</sup> calls to [next_long()](PseudoRandom::next_long). This
+    /// can be used to generate 2<sup>64</sup> starting points from each of
+    /// which [jump()](Self::jump) will generate 2<sup>64</sup> non-overlapping
+    /// subsequences for parallel distributed computations.
+    #[inline]
+    pub fn long_jump(&mut self) {
+        self.jump_by(&XOSHIRO256_LONG_JUMP);
+    }
+
+    /// Returns `n` generators whose streams are spaced 2<sup>128</sup> steps
+    /// apart. The first one starts at the current state of this generator and
+    /// this generator gets advanced by `n` [jumps](Self::jump) so that its own
+    /// subsequent stream doesn't overlap with any of the returned ones either.
+    pub fn jumps(&mut self, n: usize) -> Vec<Self> {
+        let mut generators = Vec::with_capacity(n);
+        for _ in 0..n {
+            generators.push(self.clone());
+            self.jump();
+        }
+        generators
+    }
+
+    #[inline]
+    fn jump_by(&mut self, poly: &[u64; 4]) {
+        let mut s0 = 0i64;
+        let mut s1 = 0i64;
+        let mut s2 = 0i64;
+        let mut s3 = 0i64;
+        for &word in poly {
+            for b in 0..64 {
+                if (word >> b) & 1u64 != 0u64 {
+                    s0 ^= self.x0;
+                    s1 ^= self.x1;
+                    s2 ^= self.x2;
+                    s3 ^= self.x3;
+                }
+                self.next_long();
+            }
+        }
+        self.x0 = s0;
+        self.x1 = s1;
+        self.x2 = s2;
+        self.x3 = s3;
+    }
+
     #[inline]
     fn internal_new(seeder: &mut XorShift128Plus) -> Self {
         let mut instance = XoShiRo256StarStar {
@@ -392,6 +944,40 @@ impl XoShiRo256StarStar {
     }
 }
 
+impl Advance for XoShiRo256StarStar {
+    #[inline]
+    fn advance(&mut self, n: u128) {
+        self.jump_by(&x_pow_mod(n, &XOSHIRO256_CHAR_POLY));
+    }
+}
+
+impl Seedable for XoShiRo256StarStar {
+    type Seed = [u8; 32];
+
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        let state = [
+            seed_word(&seed, 0),
+            seed_word(&seed, 1),
+            seed_word(&seed, 2),
+            seed_word(&seed, 3),
+        ];
+        XoShiRo256StarStar::new_from_state(state).unwrap_or_else(|_| Self::seed_from_u64(0u64))
+    }
+
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_seed(expand_seed(SplitMix64::new_from(state as i64)))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn from_entropy() -> Self {
+        Self::from_seed(crate::seed::system_seed())
+    }
+}
+
+#[cfg(feature = "std")]
 impl Default for XoShiRo256StarStar {
     #[inline]
     fn default() -> Self {
@@ -405,7 +991,7 @@ impl Default for XoShiRo256StarStar {
  * for dimensions 2 through 8 for the multiplier 0xd1342543de82ef95 are
  * [0.958602, 0.937479, 0.870757, 0.822326, 0.820405, 0.813065, 0.760215].
  */
-const M: i64 = 0xd1342543de82ef95u64 as i64;
+pub(crate) const M: i64 = 0xd1342543de82ef95u64 as i64;
 
 /// The `L64X1024MixRandom` algorithm from JDK 17 which uses a linear
 /// congruential generator (LCG) as a first subgenerator and a Xor-based
@@ -419,6 +1005,7 @@ const M: i64 = 0xd1342543de82ef95u64 as i64;
 ///
 /// This is the slowest generator supplied in this crate.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Lcg64Xor1024Mix {
     /*
      * The parameter that is used as an additive constant for the LCG.
@@ -456,7 +1043,20 @@ impl PseudoRandom for Lcg64Xor1024Mix {
 }
 
 impl Lcg64Xor1024Mix {
+    // the xoroshiro1024 state transition without the LCG update
+    #[inline]
+    fn xbg_step(&mut self) {
+        let p = self.pos;
+        let mut s15 = self.seed[p];
+        self.pos = (p + 1) & 15;
+        let s0 = self.seed[self.pos];
+        s15 ^= s0;
+        self.seed[p] = ((s0 << 25) | (s0 as u64 >> 39) as i64) ^ s15 ^ (s15 << 27);
+        self.seed[self.pos] = (s15 << 36) | (s15 as u64 >> 28) as i64;
+    }
+
     /// Creates a new [Lcg64Xor1024Mix](Lcg64Xor1024Mix) initialized with a random seed.
+    #[cfg(feature = "std")]
     #[inline]
     pub fn new() -> Self {
         Lcg64Xor1024Mix::internal_new(&mut XorShift128Plus::new())
@@ -468,10 +1068,70 @@ impl Lcg64Xor1024Mix {
         Lcg64Xor1024Mix::internal_new(&mut XorShift128Plus::new_from(seed))
     }
 
+    /// Creates a new [Lcg64Xor1024Mix](Lcg64Xor1024Mix) from its complete state:
+    /// the additive constant `a` and the state `s` of the LCG and the 16 state
+    /// words of the xoroshiro1024 subgenerator. The result produces the same
+    /// stream as the JDK 17 constructor `L64X1024MixRandom(a, s, x0, ..., x15)`
+    /// does for an odd `a`.
+    ///
+    /// Returns an error if `a` is even or if all 16 words of `seed` are zero.
+    #[inline]
+    pub fn new_from_state(a: i64, s: i64, seed: [i64; 16]) -> Result<Self, IllegalStateError> {
+        check_odd(a as i128)?;
+        check_nonzero(&seed)?;
+        Ok(Lcg64Xor1024Mix {
+            a,
+            s,
+            pos: 15usize,
+            seed,
+        })
+    }
+
+    /// Creates a new [Lcg64Xor1024Mix](Lcg64Xor1024Mix) that is seeded in the
+    /// same way as by JDK 17's `L64X1024MixRandom(long seed)` constructor, i.e.,
+    /// both produce the identical stream for the same `seed`.
+    #[inline]
+    pub fn new_java(seed: i64) -> Self {
+        Lcg64Xor1024Mix {
+            a: murmur_mix64(seed ^ SILVER) | 1i64,
+            s: 1i64,
+            pos: 15usize,
+            seed: java_seed_words(seed),
+        }
+    }
+
+    /// Returns the state of this generator in the stable, versioned
+    /// [binary state format](self#binary-state-format).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new(TAG_LCG64_XOR1024_MIX, 144);
+        writer.put_i64(self.a);
+        writer.put_i64(self.s);
+        // rotate the array such that pos would be 15
+        for k in 0..16 {
+            writer.put_i64(self.seed[(k + self.pos + 1) & 15]);
+        }
+        writer.finish()
+    }
+
+    /// Creates a new [Lcg64Xor1024Mix](Lcg64Xor1024Mix) from a state in the
+    /// [binary state format](self#binary-state-format) which has been produced
+    /// by [to_bytes()](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StateFormatError> {
+        let mut reader = StateReader::open(bytes, TAG_LCG64_XOR1024_MIX, 144)?;
+        let a = reader.i64();
+        let s = reader.i64();
+        let mut seed = [0i64; 16];
+        for x in seed.iter_mut() {
+            *x = reader.i64();
+        }
+        Ok(Lcg64Xor1024Mix::new_from_state(a, s, seed)?)
+    }
+
     #[inline]
     fn internal_new(seeder: &mut XorShift128Plus) -> Self {
         let mut instance = Lcg64Xor1024Mix {
-            a: seeder.next_long(),
+            // a must be odd
+            a: seeder.next_long() | 1i64,
             s: seeder.next_long(),
             pos: 15usize,
             seed: [0i64; 16],
@@ -483,44 +1143,94 @@ impl Lcg64Xor1024Mix {
     }
 }
 
-impl Default for Lcg64Xor1024Mix {
+impl Splittable for Lcg64Xor1024Mix {
+    /// Returns a new [Lcg64Xor1024Mix](Lcg64Xor1024Mix) in the same way as JDK 17's
+    /// `L64X1024MixRandom.split()` does: the additive constant `a` of the new
+    /// LCG is chosen at random (and therefore differs from the parent's with
+    /// very high probability) and the LCG and xoroshiro1024 states are freshly
+    /// drawn from this generator.
     #[inline]
-    fn default() -> Self {
-        Self::new()
+    fn split(&mut self) -> Self {
+        let a = (self.next_long() << 1) | 1i64;
+        let s = self.next_long();
+        let mut seed = [0i64; 16];
+        for x in seed.iter_mut() {
+            *x = self.next_long();
+        }
+        if seed.iter().all(|&x| x == 0i64) {
+            let mut v = s;
+            for x in seed.iter_mut() {
+                v = v.wrapping_add(GOLDEN);
+                *x = stafford_mix13(v);
+            }
+        }
+        Lcg64Xor1024Mix {
+            a,
+            s,
+            pos: 15usize,
+            seed,
+        }
     }
 }
 
-/// A thread-local generator that wraps a [Stc64](Stc64) generator.
-#[derive(Debug, Copy, Clone)]
-pub struct ThreadLocalPrng {
-    prng: NonNull<Stc64>,
+impl Advance for Lcg64Xor1024Mix {
+    #[inline]
+    fn advance(&mut self, n: u128) {
+        self.s = lcg_advance(self.s, M, self.a, n);
+        let poly = x_pow_mod(n, &XOROSHIRO1024_CHAR_POLY);
+        let mut t = [0i64; 16];
+        for &word in poly.iter() {
+            for b in 0..64 {
+                if (word >> b) & 1u64 != 0u64 {
+                    for (j, x) in t.iter_mut().enumerate() {
+                        *x ^= self.seed[(j + self.pos) & 15];
+                    }
+                }
+                self.xbg_step();
+            }
+        }
+        for (j, &x) in t.iter().enumerate() {
+            self.seed[(j + self.pos) & 15] = x;
+        }
+    }
 }
 
-impl PseudoRandom for ThreadLocalPrng {
+impl Seedable for Lcg64Xor1024Mix {
+    type Seed = [u8; 144];
+
     #[inline]
-    fn next_long(&mut self) -> i64 {
-        unsafe { self.prng.as_mut().next_long() }
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut xbg = [0i64; 16];
+        for (i, x) in xbg.iter_mut().enumerate() {
+            *x = seed_word(&seed, i + 2);
+        }
+        if xbg.iter().all(|&x| x == 0i64) {
+            xbg = Self::seed_from_u64(0u64).seed;
+        }
+        Lcg64Xor1024Mix {
+            a: seed_word(&seed, 0) | 1i64,
+            s: seed_word(&seed, 1),
+            pos: 15usize,
+            seed: xbg,
+        }
     }
-}
 
-impl ThreadLocalPrng {
-    /// Get a thread-local generator that wraps a [Stc64](Stc64) generator.
     #[inline]
-    pub fn get() -> Self {
-        let ptr = THREAD_LOCAL_PRNG_KEY.with(|t| t.get());
-        let stc64 = NonNull::new(ptr).unwrap();
-        ThreadLocalPrng { prng: stc64 }
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_seed(expand_seed(SplitMix64::new_from(state as i64)))
     }
-}
 
-thread_local!(
-    static THREAD_LOCAL_PRNG_KEY: UnsafeCell<Stc64> = {
-        UnsafeCell::new(Stc64::new())
+    #[cfg(feature = "std")]
+    #[inline]
+    fn from_entropy() -> Self {
+        Self::from_seed(crate::seed::system_seed())
     }
-);
+}
 
-impl Default for ThreadLocalPrng {
-    fn default() -> ThreadLocalPrng {
-        Self::get()
+#[cfg(feature = "std")]
+impl Default for Lcg64Xor1024Mix {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -0,0 +1,218 @@
+// Copyright 2022 Stefan Zobel
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Interoperability with the `rand_core` traits (enabled by the `rand_core` feature).
+
+#[cfg(feature = "std")]
+use crate::pseudo_random::ThreadLocalPrng;
+use crate::pseudo_random::{Lcg64Xor1024Mix, PseudoRandom, Seedable, Stc64, XoShiRo256StarStar};
+use rand_core::{Error, RngCore, SeedableRng};
+
+macro_rules! impl_rng_core {
+    ($($t:ty),*) => {
+        $(
+            impl RngCore for $t {
+                #[inline]
+                fn next_u32(&mut self) -> u32 {
+                    self.next_int() as u32
+                }
+
+                #[inline]
+                fn next_u64(&mut self) -> u64 {
+                    self.next_long() as u64
+                }
+
+                #[inline]
+                fn fill_bytes(&mut self, dest: &mut [u8]) {
+                    self.next_bytes(dest);
+                }
+
+                #[inline]
+                fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+                    self.next_bytes(dest);
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_rng_core!(Stc64, XoShiRo256StarStar, Lcg64Xor1024Mix);
+#[cfg(feature = "std")]
+impl_rng_core!(ThreadLocalPrng);
+
+/// A byte array seed for generators whose state is larger than 32 bytes.
+/// `rand_core`'s [SeedableRng](rand_core::SeedableRng) requires a seed type
+/// that implements `Default` which arrays of this size don't.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SeedBytes<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> Default for SeedBytes<N> {
+    #[inline]
+    fn default() -> Self {
+        SeedBytes([0u8; N])
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for SeedBytes<N> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> AsMut<[u8]> for SeedBytes<N> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl SeedableRng for Stc64 {
+    type Seed = SeedBytes<40>;
+
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Seedable::from_seed(seed.0)
+    }
+
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Seedable::seed_from_u64(state)
+    }
+}
+
+impl SeedableRng for XoShiRo256StarStar {
+    type Seed = [u8; 32];
+
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Seedable::from_seed(seed)
+    }
+
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Seedable::seed_from_u64(state)
+    }
+}
+
+impl SeedableRng for Lcg64Xor1024Mix {
+    type Seed = SeedBytes<144>;
+
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Seedable::from_seed(seed.0)
+    }
+
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Seedable::seed_from_u64(state)
+    }
+}
+
+/// An adapter that makes any `rand_core` [RngCore](rand_core::RngCore) usable
+/// as a [PseudoRandom](crate::pseudo_random::PseudoRandom).
+#[derive(Debug, Clone)]
+pub struct RngCoreAdapter<R: RngCore> {
+    rng: R,
+}
+
+impl<R: RngCore> RngCoreAdapter<R> {
+    /// Wraps the given `rng`.
+    #[inline]
+    pub fn new(rng: R) -> Self {
+        RngCoreAdapter { rng }
+    }
+
+    /// Returns the wrapped generator.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+impl<R: RngCore> PseudoRandom for RngCoreAdapter<R> {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        self.rng.next_u64() as i64
+    }
+
+    #[inline]
+    fn next_int(&mut self) -> i32 {
+        self.rng.next_u32() as i32
+    }
+
+    #[inline]
+    fn next_bytes(&mut self, bytes: &mut [u8]) {
+        self.rng.fill_bytes(bytes);
+    }
+}
+
+#[cfg(test)]
+mod rand_compat_tests {
+    use super::*;
+
+    fn check_rng_core<R: RngCore + PseudoRandom + Clone>(rng: R) {
+        let mut r1 = rng.clone();
+        let mut r2 = rng;
+        assert_eq!(r1.next_u64(), r2.next_long() as u64);
+        assert_eq!(r1.next_u32(), r2.next_int() as u32);
+        let mut b1 = [0u8; 13];
+        let mut b2 = [0u8; 13];
+        r1.fill_bytes(&mut b1);
+        r2.next_bytes(&mut b2);
+        assert_eq!(b1, b2);
+        assert!(r1.try_fill_bytes(&mut b1).is_ok());
+    }
+
+    #[test]
+    fn test_rng_core() {
+        check_rng_core(Stc64::new_from(42i64));
+        check_rng_core(XoShiRo256StarStar::new_from(42i64));
+        check_rng_core(Lcg64Xor1024Mix::new_from(42i64));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_thread_local_rng_core() {
+        let mut prng = ThreadLocalPrng::get();
+        assert_ne!(prng.next_u64(), prng.next_u64());
+    }
+
+    fn check_seedable_rng<R>()
+    where
+        R: SeedableRng + Seedable + PseudoRandom + RngCore,
+    {
+        let mut r1 = <R as SeedableRng>::seed_from_u64(7u64);
+        let mut r2 = <R as Seedable>::seed_from_u64(7u64);
+        assert_eq!(r1.next_long(), r2.next_long());
+        let mut r3 = <R as SeedableRng>::from_rng(&mut r1).unwrap();
+        assert_ne!(r3.next_long(), r2.next_long());
+    }
+
+    #[test]
+    fn test_seedable_rng() {
+        check_seedable_rng::<Stc64>();
+        check_seedable_rng::<XoShiRo256StarStar>();
+        check_seedable_rng::<Lcg64Xor1024Mix>();
+        let seed = SeedBytes([3u8; 40]);
+        let mut r1 = <Stc64 as SeedableRng>::from_seed(seed);
+        let mut r2 = <Stc64 as Seedable>::from_seed([3u8; 40]);
+        assert_eq!(r1.next_long(), r2.next_long());
+    }
+
+    #[test]
+    fn test_rng_core_adapter() {
+        let mut adapter = RngCoreAdapter::new(XoShiRo256StarStar::new_from(1i64));
+        let mut prng = XoShiRo256StarStar::new_from(1i64);
+        assert_eq!(adapter.next_long(), prng.next_long());
+        assert_eq!(adapter.next_int(), prng.next_int());
+        assert_eq!(adapter.next_double(), prng.next_double());
+        let mut inner = adapter.into_inner();
+        assert_eq!(inner.next_long(), prng.next_long());
+    }
+}
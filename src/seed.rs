@@ -6,11 +6,17 @@
 // copied, modified, or distributed except according to those terms.
 
 use crate::bit_mix;
+use crate::global_seed::global_seed;
+use crate::pseudo_random::expand_seed;
+use crate::split_mix64::SplitMix64;
 use parking_lot::lock_api::Mutex;
 use parking_lot::{const_mutex, RawMutex};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-
-static mut UNUSED: u8 = 0u8;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::fs::File;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::io::{self, Read};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 static mut SEED_UNIQUIFIER: i64 = 0x1ed8b55fac9deci64;
 
@@ -20,10 +26,28 @@ static LAST_SEED: Mutex<RawMutex, i64> = const_mutex(0i64);
 const EPOCH_OFFSET: Duration = Duration::new(1640995200, 0);
 
 fn nano_time() -> i64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH + EPOCH_OFFSET)
-        .expect("time went backwards!")
-        .as_nanos() as i64
+    nanos_since_epoch(SystemTime::now())
+}
+
+// negative for a clock that is set before 2022 (e.g. an unset RTC)
+fn nanos_since_epoch(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH + EPOCH_OFFSET) {
+        Ok(elapsed) => elapsed.as_nanos() as i64,
+        Err(e) => (e.duration().as_nanos() as i64).wrapping_neg(),
+    }
+}
+
+// entropy that doesn't depend on the wall clock: the monotonic clock, the
+// per-process random keys of std's HashMap, the process and thread ids and
+// the (randomized) address of a stack variable
+fn extra_entropy() -> i64 {
+    let local = 0u8;
+    let mut hasher = RandomState::new().build_hasher();
+    Instant::now().hash(&mut hasher);
+    std::process::id().hash(&mut hasher);
+    std::thread::current().id().hash(&mut hasher);
+    (&local as *const u8 as usize).hash(&mut hasher);
+    hasher.finish() as i64
 }
 
 #[inline]
@@ -39,25 +63,53 @@ fn next_seed_uniquifier() -> i64 {
 }
 
 fn pseudo_random_seed() -> i64 {
-    let seed = next_seed_uniquifier() ^ nano_time();
+    let seed = next_seed_uniquifier() ^ nano_time() ^ extra_entropy();
     bit_mix::stafford_mix13(seed)
 }
 
-#[inline]
-pub(crate) fn black_hole(val: u8) {
-    // SAFETY: this is safe because we really don't care what gets
-    // written into the UNUSED location either by a single thread
-    // or concurrently by multiple threads, neither will we ever
-    // attempt to read that memory location
-    unsafe {
-        UNUSED = val;
+#[cfg(target_os = "linux")]
+fn getrandom(buf: &mut [u8]) -> io::Result<()> {
+    let mut filled = 0usize;
+    while filled < buf.len() {
+        let rest = &mut buf[filled..];
+        // SAFETY: the kernel writes at most rest.len() bytes into rest
+        let ret = unsafe { libc::syscall(libc::SYS_getrandom, rest.as_mut_ptr(), rest.len(), 0) };
+        if ret > 0 {
+            filled += ret as usize;
+        } else {
+            let e = io::Error::last_os_error();
+            // ENOSYS on kernels older than 3.17, EPERM in some sandboxes
+            if ret == 0 || e.raw_os_error() != Some(libc::EINTR) {
+                return Err(e);
+            }
+        }
     }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn getrandom(_buf: &mut [u8]) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+fn dev_urandom(buf: &mut [u8]) -> io::Result<()> {
+    File::open("/dev/urandom").and_then(|mut file| file.read_exact(buf))
+}
+
+// fills buf from the operating system's entropy source if there is one
+fn os_entropy(buf: &mut [u8]) -> io::Result<()> {
+    getrandom(buf).or_else(|_| dev_urandom(buf))
+}
+
+fn os_seed() -> Option<i64> {
+    let mut buf = [0u8; 8];
+    os_entropy(&mut buf).ok().map(|_| i64::from_le_bytes(buf))
 }
 
 pub(crate) fn raw_seed() -> i64 {
     let mut guard = LAST_SEED.lock();
     let last_seed = &mut *guard;
-    let mut seed = pseudo_random_seed();
+    let mut seed = os_seed().unwrap_or_else(pseudo_random_seed);
     loop {
         if seed == 0i64 || seed == *last_seed {
             seed = pseudo_random_seed();
@@ -69,6 +121,66 @@ pub(crate) fn raw_seed() -> i64 {
     seed
 }
 
+/// Returns `N` random bytes from the operating system's entropy source
+/// (the `getrandom` system call on Linux, `/dev/urandom` on other Unix
+/// systems). Where neither is available, the bytes are derived from the
+/// current time in the same way as the seeds of the generators' `new()`
+/// constructors are.
+///
+/// This function never fails or panics, not even when the system clock is
+/// set to a date before 2022. Use [try_entropy_seed()] to find out whether
+/// the operating system's entropy source is available.
+///
+/// The result can be used as a seed for [Seedable::from_seed()](crate::pseudo_random::Seedable::from_seed).
+/// This function requires the `std` feature.
+pub fn entropy_seed<const N: usize>() -> [u8; N] {
+    try_entropy_seed().unwrap_or_else(|_| expand_seed(SplitMix64::new()))
+}
+
+// the seed for from_entropy(), which honors the deterministic mode
+pub(crate) fn system_seed<const N: usize>() -> [u8; N] {
+    if global_seed().is_some() {
+        expand_seed(SplitMix64::new())
+    } else {
+        entropy_seed()
+    }
+}
+
+/// Returns `N` random bytes from the operating system's entropy source like
+/// [entropy_seed()] does but returns an [EntropyError] instead of falling
+/// back to the time-based scheme if that source is unavailable.
+///
+/// An unavailable entropy source is the only failure this function reports.
+/// The system clock isn't involved in the result, so a clock that is set to a
+/// date before 2022 is not an error here (and [entropy_seed()] handles it
+/// without panicking in its fallback).
+///
+/// This function requires the `std` feature.
+pub fn try_entropy_seed<const N: usize>() -> Result<[u8; N], EntropyError> {
+    let mut seed = [0u8; N];
+    match os_entropy(&mut seed) {
+        Ok(()) => Ok(seed),
+        Err(e) => Err(EntropyError(e)),
+    }
+}
+
+/// The error returned by [try_entropy_seed()] when the operating system's
+/// entropy source can't be read.
+#[derive(Debug)]
+pub struct EntropyError(io::Error);
+
+impl fmt::Display for EntropyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "operating system entropy source unavailable: {}", self.0)
+    }
+}
+
+impl std::error::Error for EntropyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
 #[cfg(test)]
 mod simple_seed_tests {
     use super::*;
@@ -86,14 +198,77 @@ mod simple_seed_tests {
 
     #[test]
     fn test_nano_time() {
+        // negative on machines whose clock is set to a date before 2022
         let fst = nano_time();
-        assert!(fst > 518_400_000_000_000);
         println!("fst: {}", fst);
         let snd = nano_time();
         println!("snd: {}", snd);
         assert!(snd >= fst);
     }
 
+    #[test]
+    fn test_nanos_before_epoch() {
+        assert_eq!(nanos_since_epoch(UNIX_EPOCH + EPOCH_OFFSET), 0i64);
+        assert_eq!(nanos_since_epoch(UNIX_EPOCH), -1_640_995_200_000_000_000i64);
+        let before = UNIX_EPOCH + Duration::new(1640995199, 999_999_999);
+        assert_eq!(nanos_since_epoch(before), -1i64);
+        // an unset RTC doesn't keep the time-based seeds from changing
+        let unset = nanos_since_epoch(UNIX_EPOCH + Duration::new(1, 0));
+        assert!(unset < 0i64);
+        assert_ne!(unset, nanos_since_epoch(UNIX_EPOCH + Duration::new(2, 0)));
+    }
+
+    #[test]
+    fn test_extra_entropy() {
+        let fst = extra_entropy();
+        let snd = extra_entropy();
+        println!("fst: {}", fst);
+        println!("snd: {}", snd);
+        assert_ne!(fst, snd);
+        assert_ne!(pseudo_random_seed(), pseudo_random_seed());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_os_entropy() {
+        let mut buf1 = [0u8; 32];
+        let mut buf2 = [0u8; 32];
+        assert!(os_entropy(&mut buf1).is_ok());
+        assert!(os_entropy(&mut buf2).is_ok());
+        assert_ne!(buf1, buf2);
+        assert!(dev_urandom(&mut buf1).is_ok());
+        assert_ne!(buf1, buf2);
+    }
+
+    #[test]
+    #[cfg(not(unix))]
+    fn test_os_entropy() {
+        let mut buf = [0u8; 32];
+        assert!(os_entropy(&mut buf).is_err());
+        assert!(try_entropy_seed::<32>().is_err());
+        // falls back to a pseudo random seed
+        let seed1: [u8; 32] = entropy_seed();
+        let seed2: [u8; 32] = entropy_seed();
+        assert_ne!(seed1, seed2);
+    }
+
+    #[test]
+    fn test_entropy_seed() {
+        let seed1: [u8; 40] = entropy_seed();
+        let seed2: [u8; 40] = entropy_seed();
+        assert_ne!(seed1, seed2);
+        assert_eq!(entropy_seed::<0>(), [0u8; 0]);
+        #[cfg(unix)]
+        {
+            let seed3: [u8; 40] = try_entropy_seed().unwrap();
+            assert_ne!(seed1, seed3);
+        }
+        let e = EntropyError(io::ErrorKind::Unsupported.into());
+        println!("{}", e);
+        let msg = e.to_string();
+        assert!(msg.starts_with("operating system entropy source unavailable"));
+    }
+
     //    #[test] // runs into trouble when tests run multi-threaded
     #[allow(dead_code)]
     fn test_next_seed_uniquifier() {
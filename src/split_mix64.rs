@@ -5,40 +5,74 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use crate::pseudo_random::{check_odd, expand_seed, seed_word, Advance, IllegalStateError};
+use crate::pseudo_random::{PseudoRandom, Seedable, Splittable};
+#[cfg(feature = "std")]
+use crate::split_mix64_seed::seed;
 use crate::{
-    bit_mix::rrxmrrxmsx, bit_mix::stafford_mix04, bit_mix::xnasam, split_mix64_seed::seed,
-    split_mix64_seed::seed_from, split_mix64_seed::GOLDEN,
+    bit_mix::rrxmrrxmsx, bit_mix::stafford_mix04, bit_mix::xnasam, split_mix64_seed::seed_from,
+    split_mix64_seed::GOLDEN,
 };
 
-pub(crate) struct SplitMix64 {
+/// A SplitMix64 generator in the style of Steele, Lea and Flood (2014) which
+/// only needs 128 bits of state: a Weyl sequence `state` with an odd increment
+/// `gamma` whose values get scrambled by a 64-bit mixing function.
+///
+/// The generator is splittable with the same semantics as `java.util.SplittableRandom`:
+/// the child gets the next `next_long()` value as its state and a new `gamma`
+/// derived from the next Weyl value. Its output and gamma mixing functions differ
+/// from those of the Java class though, use [JavaSplittableRandom](crate::pseudo_random::JavaSplittableRandom)
+/// where the Java stream must be reproduced exactly.
+///
+/// This generator has a period of 2<sup>64</sup>.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SplitMix64 {
     state: i64,
-    // Weyl generator step value
+    // Weyl generator step value, must be odd
     gamma: i64,
 }
 
+impl PseudoRandom for SplitMix64 {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        xnasam(self.next_seed())
+    }
+
+    #[inline]
+    fn next_int(&mut self) -> i32 {
+        stafford_mix04(self.next_seed())
+    }
+}
+
 impl SplitMix64 {
+    /// Creates a new [SplitMix64](SplitMix64) initialized with a random seed.
+    #[cfg(feature = "std")]
     #[inline]
-    pub(crate) fn new() -> Self {
+    pub fn new() -> Self {
         SplitMix64::internal_new(seed())
     }
 
-    #[allow(unused)]
+    /// Creates a new [SplitMix64](SplitMix64) initialized with the given `seed`.
     #[inline]
-    pub(crate) fn new_from(seed: i64) -> Self {
+    pub fn new_from(seed: i64) -> Self {
         SplitMix64::internal_new(seed_from(seed))
     }
 
+    /// Creates a new [SplitMix64](SplitMix64) from its complete state: the
+    /// current value `state` of the Weyl sequence and its increment `gamma`.
+    ///
+    /// Returns an error if `gamma` is even.
     #[inline]
-    pub(crate) fn next_long(&mut self) -> i64 {
-        self.state = self.state.wrapping_add(self.gamma);
-        xnasam(self.state)
+    pub fn new_from_state(state: i64, gamma: i64) -> Result<Self, IllegalStateError> {
+        check_odd(gamma as i128)?;
+        Ok(SplitMix64 { state, gamma })
     }
 
-    #[allow(unused)]
     #[inline]
-    pub(crate) fn next_int(&mut self) -> i32 {
+    fn next_seed(&mut self) -> i64 {
         self.state = self.state.wrapping_add(self.gamma);
-        stafford_mix04(self.state)
+        self.state
     }
 
     #[inline]
@@ -50,6 +84,29 @@ impl SplitMix64 {
     }
 }
 
+impl Splittable for SplitMix64 {
+    /// Returns a new [SplitMix64](SplitMix64) in the same way as
+    /// `java.util.SplittableRandom.split()` does: the next value of
+    /// `next_long()` becomes the state of the child and its `gamma`
+    /// is mixed from the next value of the Weyl sequence.
+    #[inline]
+    fn split(&mut self) -> Self {
+        let state = self.next_long();
+        SplitMix64 {
+            state,
+            gamma: mix_gamma(self.next_seed()),
+        }
+    }
+}
+
+impl Advance for SplitMix64 {
+    #[inline]
+    fn advance(&mut self, n: u128) {
+        // the Weyl sequence has a period of 2^64
+        self.state = self.state.wrapping_add(self.gamma.wrapping_mul(n as u64 as i64));
+    }
+}
+
 #[inline]
 const fn mix_gamma(mut v: i64) -> i64 {
     // force v to be odd
@@ -63,6 +120,30 @@ const fn mix_gamma(mut v: i64) -> i64 {
     }
 }
 
+impl Seedable for SplitMix64 {
+    type Seed = [u8; 16];
+
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        SplitMix64 {
+            state: seed_word(&seed, 0),
+            gamma: seed_word(&seed, 1) | 1i64,
+        }
+    }
+
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self::from_seed(expand_seed(SplitMix64::new_from(state as i64)))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn from_entropy() -> Self {
+        Self::from_seed(crate::seed::system_seed())
+    }
+}
+
+#[cfg(feature = "std")]
 impl Default for SplitMix64 {
     #[inline]
     fn default() -> Self {
@@ -5,27 +5,49 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use crate::{bit_mix::rrxmrrxmsx, seed::raw_seed};
+use crate::bit_mix::rrxmrrxmsx;
+#[cfg(feature = "std")]
+use crate::fork::{fork_generation, register_fork_handler};
+#[cfg(feature = "std")]
+use crate::global_seed::deterministic_seed;
+#[cfg(feature = "std")]
+use crate::seed::raw_seed;
+#[cfg(feature = "std")]
 use parking_lot::lock_api::{Mutex, MutexGuard};
+#[cfg(feature = "std")]
 use parking_lot::{const_mutex, RawMutex};
+#[cfg(feature = "std")]
 use std::ops::DerefMut;
 
 // the golden ratio scaled to 64 bits
 pub(crate) const GOLDEN: i64 = 0x9e3779b97f4a7c15u64 as i64;
 
-static STATE: Mutex<RawMutex, i64> = const_mutex(0i64);
+// the silver ratio scaled to 64 bits
+pub(crate) const SILVER: i64 = 0x6a09e667f3bcc909u64 as i64;
 
+// the seed state and the fork generation it was seeded in
+#[cfg(feature = "std")]
+static STATE: Mutex<RawMutex, (i64, u64)> = const_mutex((0i64, 0u64));
+
+#[cfg(feature = "std")]
 #[inline]
-fn ensure_state<'a>(guard: &'a mut MutexGuard<'_, RawMutex, i64>) -> &'a mut i64 {
-    let state = guard.deref_mut();
-    if *state == 0i64 {
+fn ensure_state<'a>(guard: &'a mut MutexGuard<'_, RawMutex, (i64, u64)>) -> &'a mut i64 {
+    let (state, generation) = guard.deref_mut();
+    if *state == 0i64 || *generation != fork_generation() {
+        // reseed in a forked child
+        register_fork_handler();
+        *generation = fork_generation();
         *state = raw_seed();
     }
     state
 }
 
+#[cfg(feature = "std")]
 #[inline]
 pub(crate) fn seed() -> i64 {
+    if let Some(seed) = deterministic_seed() {
+        return seed;
+    }
     let mut guard = STATE.lock();
     let state = ensure_state(&mut guard);
     *state = state.wrapping_add(GOLDEN);
@@ -0,0 +1,227 @@
+// Copyright 2022 Stefan Zobel
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::pseudo_random::IllegalStateError;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::fmt;
+
+/*
+ * Version 1 of the binary state format:
+ *
+ *   byte 0            format version (1)
+ *   byte 1            algorithm tag
+ *   bytes 2..(n - 4)  state payload (little-endian)
+ *   bytes (n - 4)..n  CRC-32 (IEEE 802.3) of bytes 0..(n - 4), little-endian
+ *
+ * The format of an existing version and the algorithm tags must never change.
+ */
+pub(crate) const VERSION: u8 = 1u8;
+
+pub(crate) const TAG_STC64: u8 = 1u8;
+pub(crate) const TAG_XOSHIRO256_STAR_STAR: u8 = 2u8;
+pub(crate) const TAG_LCG64_XOR1024_MIX: u8 = 3u8;
+
+const HEADER_LEN: usize = 2usize;
+const CHECKSUM_LEN: usize = 4usize;
+
+/// The error returned when the binary representation of a generator's state
+/// can't be decoded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StateFormatError {
+    /// The input has not the length that the format requires.
+    Length {
+        /// The required length in bytes.
+        expected: usize,
+        /// The actual length in bytes.
+        actual: usize,
+    },
+    /// The checksum doesn't match the content, i.e., the input is corrupt.
+    Checksum,
+    /// The format version is unknown to this version of the crate.
+    Version(u8),
+    /// The input represents the state of a different algorithm. The value is
+    /// the algorithm tag found in the input.
+    Algorithm(u8),
+    /// The decoded state is illegal for the algorithm.
+    IllegalState(IllegalStateError),
+}
+
+impl fmt::Display for StateFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateFormatError::Length { expected, actual } => {
+                write!(f, "expected {} bytes but got {}", expected, actual)
+            }
+            StateFormatError::Checksum => f.write_str("checksum mismatch"),
+            StateFormatError::Version(v) => write!(f, "unsupported format version {}", v),
+            StateFormatError::Algorithm(t) => write!(f, "unexpected algorithm tag {}", t),
+            StateFormatError::IllegalState(e) => write!(f, "illegal state: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StateFormatError {}
+
+impl From<IllegalStateError> for StateFormatError {
+    #[inline]
+    fn from(e: IllegalStateError) -> Self {
+        StateFormatError::IllegalState(e)
+    }
+}
+
+pub(crate) struct StateWriter {
+    buf: Vec<u8>,
+}
+
+impl StateWriter {
+    #[inline]
+    pub(crate) fn new(tag: u8, payload_len: usize) -> Self {
+        let mut buf = Vec::with_capacity(HEADER_LEN + payload_len + CHECKSUM_LEN);
+        buf.push(VERSION);
+        buf.push(tag);
+        StateWriter { buf }
+    }
+
+    #[inline]
+    pub(crate) fn put_i64(&mut self, v: i64) {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    #[inline]
+    pub(crate) fn finish(mut self) -> Vec<u8> {
+        let checksum = crc32(&self.buf);
+        self.buf.extend_from_slice(&checksum.to_le_bytes());
+        self.buf
+    }
+}
+
+pub(crate) struct StateReader<'a> {
+    payload: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> StateReader<'a> {
+    /// Validates length, checksum, version and algorithm tag of `bytes` and
+    /// returns a reader over the payload.
+    pub(crate) fn open(
+        bytes: &'a [u8],
+        tag: u8,
+        payload_len: usize,
+    ) -> Result<Self, StateFormatError> {
+        let expected = HEADER_LEN + payload_len + CHECKSUM_LEN;
+        if bytes.len() < HEADER_LEN + CHECKSUM_LEN {
+            return Err(StateFormatError::Length {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+        let (content, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+        if crc32(content) != u32::from_le_bytes(checksum.try_into().unwrap()) {
+            return Err(StateFormatError::Checksum);
+        }
+        if content[0] != VERSION {
+            return Err(StateFormatError::Version(content[0]));
+        }
+        if content[1] != tag {
+            return Err(StateFormatError::Algorithm(content[1]));
+        }
+        if bytes.len() != expected {
+            return Err(StateFormatError::Length {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+        Ok(StateReader {
+            payload: &content[HEADER_LEN..],
+            pos: 0usize,
+        })
+    }
+
+    #[inline]
+    pub(crate) fn i64(&mut self) -> i64 {
+        let v = i64::from_le_bytes(self.payload[self.pos..self.pos + 8].try_into().unwrap());
+        self.pos += 8;
+        v
+    }
+}
+
+// bitwise CRC-32 with the reflected IEEE 802.3 polynomial
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffffffffu32;
+    for &b in bytes {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb88320u32 & (crc & 1u32).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod state_format_tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xcbf43926u32);
+        assert_eq!(crc32(b""), 0u32);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut writer = StateWriter::new(TAG_STC64, 16);
+        writer.put_i64(-42i64);
+        writer.put_i64(7i64);
+        let bytes = writer.finish();
+        assert_eq!(bytes.len(), 22);
+        assert_eq!(&bytes[..4], &[1u8, 1u8, 0xd6u8, 0xffu8]);
+        let mut reader = StateReader::open(&bytes, TAG_STC64, 16).unwrap();
+        assert_eq!(reader.i64(), -42i64);
+        assert_eq!(reader.i64(), 7i64);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut writer = StateWriter::new(TAG_STC64, 8);
+        writer.put_i64(1i64);
+        let bytes = writer.finish();
+        assert_eq!(
+            StateReader::open(&bytes[..5], TAG_STC64, 8).err(),
+            Some(StateFormatError::Length {
+                expected: 14,
+                actual: 5
+            })
+        );
+        assert_eq!(
+            StateReader::open(&bytes, TAG_XOSHIRO256_STAR_STAR, 8).err(),
+            Some(StateFormatError::Algorithm(TAG_STC64))
+        );
+        assert_eq!(
+            StateReader::open(&bytes, TAG_STC64, 16).err(),
+            Some(StateFormatError::Length {
+                expected: 22,
+                actual: 14
+            })
+        );
+        let mut corrupt = bytes.clone();
+        corrupt[3] ^= 1u8;
+        assert_eq!(
+            StateReader::open(&corrupt, TAG_STC64, 8).err(),
+            Some(StateFormatError::Checksum)
+        );
+        let mut future = bytes[..10].to_vec();
+        future[0] = 2u8;
+        let checksum = crc32(&future);
+        future.extend_from_slice(&checksum.to_le_bytes());
+        assert_eq!(
+            StateReader::open(&future, TAG_STC64, 8).err(),
+            Some(StateFormatError::Version(2u8))
+        );
+    }
+}
@@ -0,0 +1,38 @@
+// Copyright 2022 Stefan Zobel
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//!
+//! Checks shared by the unit tests of several modules.
+//!
+
+use crate::pseudo_random::{Advance, PseudoRandom};
+
+// checks that advance(n) is equivalent to n calls to next_long(), also from
+// a position inside of a block for the counter-based generators
+pub(crate) fn check_advance<G: PseudoRandom + Advance + Clone>(prng: G) {
+    let mut prng = prng;
+    for _ in 0..2 {
+        for &n in &[0u128, 1u128, 2u128, 3u128, 5u128, 17u128, 300u128, 1500u128] {
+            let mut stepped = prng.clone();
+            for _ in 0..n {
+                stepped.next_long();
+            }
+            let mut advanced = prng.clone();
+            advanced.advance(n);
+            for _ in 0..20 {
+                assert_eq!(advanced.next_long(), stepped.next_long());
+            }
+        }
+        prng.next_long();
+    }
+    let mut prng1 = prng.clone();
+    prng1.advance(123_456_789u128);
+    prng1.advance(987_654_321_000u128);
+    let mut prng2 = prng;
+    prng2.advance(987_777_777_789u128);
+    assert_eq!(prng1.next_long(), prng2.next_long());
+}
@@ -0,0 +1,375 @@
+// Copyright 2022 Stefan Zobel
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::fork::{fork_generation, register_fork_handler};
+use crate::pseudo_random::{JavaRandom, JavaSplittableRandom, Lcg64Xor1024Mix, PseudoRandom};
+use crate::pseudo_random::{Lcg128Xor1024Mix, Lcg128Xor128Mix, Lcg128Xor256Mix};
+use crate::pseudo_random::{Lcg32Xor64Mix, Lcg64Xor128Mix, Lcg64Xor128StarStar, Lcg64Xor256Mix};
+use crate::pseudo_random::{Pcg32, Pcg64, Pcg64Dxsm, Philox4x32, Philox4x64, Threefry2x64};
+use crate::pseudo_random::{SplitMix64, Stc64, XoShiRo256StarStar, Xoshiro256PlusPlus};
+use crate::pseudo_random::{Xoroshiro1024StarStar, Xoroshiro128PlusPlus, Xoroshiro128StarStar};
+use crate::pseudo_random::{Xoshiro256Plus, Xoshiro512StarStar};
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::marker::PhantomData;
+
+/// A thread-local generator that wraps a [Stc64](crate::pseudo_random::Stc64) generator.
+pub type ThreadLocalPrng = ThreadLocal<Stc64>;
+
+/// A generator that has a lazily seeded instance per thread which can be used
+/// through [ThreadLocal](ThreadLocal). This trait is implemented by all generators
+/// of this crate that have a `new()` constructor and can't be implemented outside
+/// of it.
+pub trait ThreadLocalGenerator: PseudoRandom + Clone + private::Sealed {}
+
+mod private {
+    pub trait Sealed: Sized {
+        // Runs f on the current thread's generator. f must not access the
+        // thread-local generator itself.
+        fn with_thread_local<R>(f: impl FnOnce(&mut Self) -> R) -> R;
+
+        // new_from(seed)
+        fn from_i64_seed(seed: i64) -> Self;
+
+        // new()
+        fn from_system_seed() -> Self;
+    }
+}
+
+macro_rules! impl_thread_local {
+    ($($t:ident),*) => {
+        $(
+            impl private::Sealed for $t {
+                #[inline]
+                fn with_thread_local<R>(f: impl FnOnce(&mut Self) -> R) -> R {
+                    thread_local!(
+                        // the generator and the fork generation it was seeded in
+                        static KEY: UnsafeCell<($t, u64)> = {
+                            register_fork_handler();
+                            UnsafeCell::new(($t::new(), fork_generation()))
+                        }
+                    );
+                    let mut f = Some(f);
+                    KEY.try_with(|t| {
+                        // SAFETY: the generator is only ever accessed through this
+                        // function, which doesn't reenter, so the mutable reference
+                        // is unique for the duration of the call
+                        let (prng, generation) = unsafe { &mut *t.get() };
+                        if *generation != fork_generation() {
+                            // reseed in a forked child
+                            *generation = fork_generation();
+                            *prng = $t::new();
+                        }
+                        (f.take().unwrap())(prng)
+                    })
+                    .unwrap_or_else(|_| (f.take().unwrap())(&mut $t::new()))
+                }
+
+                #[inline]
+                fn from_i64_seed(seed: i64) -> Self {
+                    $t::new_from(seed)
+                }
+
+                #[inline]
+                fn from_system_seed() -> Self {
+                    $t::new()
+                }
+            }
+
+            impl ThreadLocalGenerator for $t {}
+        )*
+    };
+}
+
+impl_thread_local!(
+    Stc64,
+    XoShiRo256StarStar,
+    Lcg64Xor1024Mix,
+    Xoshiro256PlusPlus,
+    SplitMix64
+);
+impl_thread_local!(
+    Lcg32Xor64Mix,
+    Lcg64Xor128Mix,
+    Lcg64Xor128StarStar,
+    Lcg64Xor256Mix
+);
+impl_thread_local!(Lcg128Xor128Mix, Lcg128Xor256Mix, Lcg128Xor1024Mix);
+impl_thread_local!(JavaRandom, JavaSplittableRandom);
+impl_thread_local!(Xoshiro256Plus, Xoshiro512StarStar, Xoroshiro1024StarStar);
+impl_thread_local!(Xoroshiro128PlusPlus, Xoroshiro128StarStar);
+impl_thread_local!(Pcg32, Pcg64, Pcg64Dxsm);
+impl_thread_local!(Philox4x32, Philox4x64, Threefry2x64);
+
+/// A handle to the thread-local instance of the generator `G` which gets
+/// seeded by `G::new()` when a thread uses it for the first time. For example,
+/// `ThreadLocal::<Lcg64Xor1024Mix>::get()` provides a 16-dimensionally
+/// equidistributed stream on every thread without the need to pass a
+/// generator around.
+///
+/// The handle itself has no state: every call is forwarded to the generator of
+/// the thread that makes the call. Copies of a handle (or a handle that has
+/// been sent to another thread) therefore never alias a generator and can't
+/// dangle. Calls from thread-local destructors that run after the thread's
+/// generator has been destroyed are served by a temporary, freshly seeded
+/// generator.
+pub struct ThreadLocal<G: ThreadLocalGenerator> {
+    _marker: PhantomData<fn() -> G>,
+}
+
+impl<G: ThreadLocalGenerator> PseudoRandom for ThreadLocal<G> {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        G::with_thread_local(|prng| prng.next_long())
+    }
+
+    #[inline]
+    fn next_int(&mut self) -> i32 {
+        G::with_thread_local(|prng| prng.next_int())
+    }
+
+    #[inline]
+    fn next_double(&mut self) -> f64 {
+        G::with_thread_local(|prng| prng.next_double())
+    }
+
+    #[inline]
+    fn next_float(&mut self) -> f32 {
+        G::with_thread_local(|prng| prng.next_float())
+    }
+
+    #[inline]
+    fn next_bool(&mut self) -> bool {
+        G::with_thread_local(|prng| prng.next_bool())
+    }
+
+    #[inline]
+    fn next_int_up_to(&mut self, n: i32) -> i32 {
+        G::with_thread_local(|prng| prng.next_int_up_to(n))
+    }
+
+    #[inline]
+    fn next_bytes(&mut self, bytes: &mut [u8]) {
+        G::with_thread_local(|prng| prng.next_bytes(bytes))
+    }
+
+    #[inline]
+    fn next_longs(&mut self, longs: &mut [i64]) {
+        G::with_thread_local(|prng| prng.next_longs(longs))
+    }
+
+    #[inline]
+    fn next_doubles(&mut self, doubles: &mut [f64]) {
+        G::with_thread_local(|prng| prng.next_doubles(doubles))
+    }
+}
+
+impl<G: ThreadLocalGenerator> ThreadLocal<G> {
+    /// Get a handle to the thread-local instance of the generator `G`.
+    #[inline]
+    pub fn get() -> Self {
+        ThreadLocal {
+            _marker: PhantomData,
+        }
+    }
+
+    /// Reseeds the current thread's instance of `G` with the given `seed` in
+    /// the same way as `G::new_from(seed)` does. Other threads are not affected.
+    pub fn reseed(seed: i64) {
+        Self::restore(G::from_i64_seed(seed));
+    }
+
+    /// Reseeds the current thread's instance of `G` with a random seed in the
+    /// same way as `G::new()` does. Other threads are not affected.
+    ///
+    /// Like `G::new()` and `from_entropy()`, this is deterministic when the
+    /// deterministic mode has been enabled by
+    /// [set_global_seed()](crate::set_global_seed) or by the `RND_SEED`
+    /// environment variable.
+    pub fn reseed_from_entropy() {
+        Self::restore(G::from_system_seed());
+    }
+
+    /// Returns a copy of the current state of this thread's instance of `G`.
+    /// Drawing values from the copy doesn't advance the thread-local generator.
+    pub fn snapshot() -> G {
+        G::with_thread_local(|prng| prng.clone())
+    }
+
+    /// Replaces the state of the current thread's instance of `G` with `state`,
+    /// e.g. with a state that has been saved by [snapshot()](Self::snapshot).
+    ///
+    /// Together with [reseed()](Self::reseed) this allows a test to make the
+    /// thread-local randomness deterministic for its duration and to continue
+    /// with the previous stream afterwards.
+    pub fn restore(state: G) {
+        G::with_thread_local(|prng| *prng = state);
+    }
+}
+
+impl<G: ThreadLocalGenerator> Clone for ThreadLocal<G> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<G: ThreadLocalGenerator> Copy for ThreadLocal<G> {}
+
+impl<G: ThreadLocalGenerator> fmt::Debug for ThreadLocal<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ThreadLocal<{}>", core::any::type_name::<G>())
+    }
+}
+
+impl<G: ThreadLocalGenerator> Default for ThreadLocal<G> {
+    fn default() -> ThreadLocal<G> {
+        Self::get()
+    }
+}
+
+#[cfg(test)]
+mod thread_local_prng_tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_copies() {
+        let mut prng1 = ThreadLocalPrng::get();
+        let mut prng2 = prng1;
+        let mut prng3 = ThreadLocalPrng::default();
+        let mut values = Vec::new();
+        for _ in 0..4 {
+            values.push(prng1.next_long());
+            values.push(prng2.next_long());
+            values.push(prng3.next_long());
+        }
+        println!("{:?}", values);
+        values.sort_unstable();
+        values.dedup();
+        assert_eq!(values.len(), 12);
+    }
+
+    #[test]
+    fn test_moved_to_other_thread() {
+        let mut prng = ThreadLocalPrng::get();
+        let l = prng.next_long();
+        let other = thread::spawn(move || prng.next_long()).join().unwrap();
+        assert_ne!(l, other);
+        assert_ne!(prng.next_long(), other);
+    }
+
+    struct DrawOnDrop;
+
+    impl Drop for DrawOnDrop {
+        fn drop(&mut self) {
+            let mut prng = ThreadLocalPrng::get();
+            println!("in TLS destructor: {}", prng.next_long());
+        }
+    }
+
+    thread_local!(
+        static DRAW_ON_DROP: DrawOnDrop = DrawOnDrop
+    );
+
+    #[test]
+    fn test_thread_local_destructor() {
+        thread::spawn(|| {
+            DRAW_ON_DROP.with(|_| {});
+            ThreadLocalPrng::get().next_long();
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_generic() {
+        let mut lcg = ThreadLocal::<Lcg64Xor1024Mix>::get();
+        let mut xoshiro: ThreadLocal<Xoshiro256PlusPlus> = ThreadLocal::default();
+        let mut java = ThreadLocal::<JavaRandom>::get();
+        println!("{:?}", lcg);
+        assert_ne!(lcg.next_long(), lcg.next_long());
+        assert_ne!(xoshiro.next_double(), xoshiro.next_double());
+        let mut bytes = [0u8; 13];
+        java.next_bytes(&mut bytes);
+        assert_ne!(bytes, [0u8; 13]);
+        let n = java.next_int_up_to(10i32);
+        assert!((0i32..10i32).contains(&n));
+    }
+
+    #[test]
+    fn test_reseed() {
+        ThreadLocalPrng::reseed(42i64);
+        let mut prng = ThreadLocalPrng::get();
+        let mut expected = Stc64::new_from(42i64);
+        for _ in 0..8 {
+            assert_eq!(prng.next_long(), expected.next_long());
+        }
+        ThreadLocal::<JavaRandom>::reseed(42i64);
+        let mut java = ThreadLocal::<JavaRandom>::get();
+        assert_eq!(java.next_int(), -1170105035i32);
+        ThreadLocalPrng::reseed_from_entropy();
+        assert_ne!(prng.next_long(), expected.next_long());
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let mut prng = ThreadLocal::<Lcg64Xor1024Mix>::get();
+        let mut snapshot = ThreadLocal::<Lcg64Xor1024Mix>::snapshot();
+        let values: Vec<i64> = (0..20).map(|_| prng.next_long()).collect();
+        let copied: Vec<i64> = (0..20).map(|_| snapshot.next_long()).collect();
+        assert_eq!(values, copied);
+        // the copy has its own state
+        let next = prng.next_long();
+        ThreadLocal::<Lcg64Xor1024Mix>::restore(ThreadLocal::<Lcg64Xor1024Mix>::snapshot());
+        assert_ne!(next, prng.next_long());
+        let saved = ThreadLocal::<Lcg64Xor1024Mix>::snapshot();
+        let fst = prng.next_long();
+        ThreadLocal::<Lcg64Xor1024Mix>::restore(saved);
+        assert_eq!(fst, prng.next_long());
+    }
+
+    #[test]
+    fn test_reseed_is_thread_local() {
+        ThreadLocalPrng::reseed(7i64);
+        let other = thread::spawn(|| {
+            ThreadLocalPrng::reseed(8i64);
+            ThreadLocalPrng::get().next_long()
+        })
+        .join()
+        .unwrap();
+        assert_eq!(other, Stc64::new_from(8i64).next_long());
+        assert_eq!(
+            ThreadLocalPrng::get().next_long(),
+            Stc64::new_from(7i64).next_long()
+        );
+    }
+
+    #[test]
+    fn test_separate_instances() {
+        let draw = || {
+            ThreadLocalPrng::reseed(77i64);
+            ThreadLocal::<XoShiRo256StarStar>::reseed(77i64);
+            let mut stc = ThreadLocalPrng::get();
+            let mut xoshiro = ThreadLocal::<XoShiRo256StarStar>::get();
+            let s1 = stc.next_long();
+            let x1 = xoshiro.next_long();
+            (s1, x1, stc.next_long(), xoshiro.next_long())
+        };
+        let spawn = |name: &str| thread::Builder::new().name(name.to_string()).spawn(draw);
+        let fst = spawn("tl-generic-1").unwrap().join().unwrap();
+        let snd = spawn("tl-generic-2").unwrap().join().unwrap();
+        assert_eq!(fst, snd);
+        let mut stc = Stc64::new_from(77i64);
+        let mut xoshiro = XoShiRo256StarStar::new_from(77i64);
+        assert_ne!(fst.0, fst.1);
+        assert_eq!(fst.0, stc.next_long());
+        assert_eq!(fst.1, xoshiro.next_long());
+        assert_eq!(fst.2, stc.next_long());
+        assert_eq!(fst.3, xoshiro.next_long());
+    }
+}
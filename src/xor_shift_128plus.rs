@@ -5,7 +5,7 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use crate::seed::black_hole;
+use crate::pseudo_random::{black_hole, PseudoRandom};
 use crate::split_mix64::SplitMix64;
 
 pub(crate) struct XorShift128Plus {
@@ -25,6 +25,7 @@ impl XorShift128Plus {
         s
     }
 
+    #[cfg(feature = "std")]
     #[inline]
     pub(crate) fn new() -> Self {
         let mut rng = SplitMix64::new();
@@ -61,6 +62,7 @@ impl XorShift128Plus {
     }
 }
 
+#[cfg(feature = "std")]
 impl Default for XorShift128Plus {
     #[inline]
     fn default() -> Self {
@@ -0,0 +1,1052 @@
+// Copyright 2022 Stefan Zobel
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Further members of Blackman and Vigna's xoshiro / xoroshiro family of
+//! generators. [XoShiRo256StarStar](crate::pseudo_random::XoShiRo256StarStar)
+//! lives in the `pseudo_random` module itself.
+
+use crate::jump_ahead::x_pow_mod;
+use crate::pseudo_random::{black_hole, Advance, IllegalStateError, PseudoRandom, Seedable};
+use crate::pseudo_random::{check_nonzero, expand_seed, java_seed_words, seed_word};
+use crate::split_mix64::SplitMix64;
+use crate::xor_shift_128plus::XorShift128Plus;
+use alloc::vec::Vec;
+
+/*
+ * The jump polynomials of xoshiro256 from Blackman and Vigna's reference
+ * implementation. They correspond to 2^128 and 2^192 calls to next_long().
+ */
+pub(crate) const XOSHIRO256_JUMP: [u64; 4] = [
+    0x180ec6d33cfd0abau64,
+    0xd5a61266f0c9392cu64,
+    0xa9582618e03fc9aau64,
+    0x39abdc4529b1661cu64,
+];
+pub(crate) const XOSHIRO256_LONG_JUMP: [u64; 4] = [
+    0x76e15d3efefdcbbfu64,
+    0xc5004e441c522fb3u64,
+    0x77710069854ee241u64,
+    0x39109bb02acbe635u64,
+];
+
+/*
+ * The lower 256 coefficients of the (monic, degree 256) characteristic
+ * polynomial of xoshiro256's state transition matrix.
+ */
+pub(crate) const XOSHIRO256_CHAR_POLY: [u64; 4] = [
+    0x9d116f2bb0f0f001u64,
+    0x0280002bcefd1a5eu64,
+    0x04b4edcf26259f85u64,
+    0x0003c03c3f3ecb19u64,
+];
+
+/*
+ * The jump polynomials of xoroshiro128++ from Blackman and Vigna's reference
+ * implementation. They correspond to 2^64 and 2^96 calls to next_long().
+ */
+const XOROSHIRO128PP_JUMP: [u64; 2] = [0x2bd7a6a6e99c2ddcu64, 0x0992ccaf6a6fca05u64];
+const XOROSHIRO128PP_LONG_JUMP: [u64; 2] = [0x360fd5f2cf8d5d99u64, 0x9c6e6877736c46e3u64];
+
+/*
+ * The lower 128 coefficients of the (monic, degree 128) characteristic
+ * polynomial of xoroshiro128++'s state transition matrix.
+ */
+const XOROSHIRO128PP_CHAR_POLY: [u64; 2] = [0x8dae70779760b081u64, 0x0031bcf2f855d6e5u64];
+
+/*
+ * The jump polynomials of xoroshiro128** (and xoroshiro128+) from Blackman
+ * and Vigna's reference implementation. They correspond to 2^64 and 2^96
+ * calls to next_long().
+ */
+const XOROSHIRO128_JUMP: [u64; 2] = [0xdf900294d8f554a5u64, 0x170865df4b3201fcu64];
+const XOROSHIRO128_LONG_JUMP: [u64; 2] = [0xd2a98b26625eee7bu64, 0xdddf9b1090aa7ac1u64];
+
+/*
+ * The lower 128 coefficients of the (monic, degree 128) characteristic
+ * polynomial of xoroshiro128's (version 1.0) state transition matrix.
+ */
+pub(crate) const XOROSHIRO128_CHAR_POLY: [u64; 2] = [0x095b8f76579aa001u64, 0x0008828e513b43d5u64];
+
+/*
+ * The jump polynomials of xoshiro512 from Blackman and Vigna's reference
+ * implementation. They correspond to 2^256 and 2^384 calls to next_long().
+ */
+const XOSHIRO512_JUMP: [u64; 8] = [
+    0x33ed89b6e7a353f9u64,
+    0x760083d7955323beu64,
+    0x2837f2fbb5f22faeu64,
+    0x4b8c5674d309511cu64,
+    0xb11ac47a7ba28c25u64,
+    0xf1be7667092bcc1cu64,
+    0x53851efdb6df0aafu64,
+    0x1ebbc8b23eaf25dbu64,
+];
+const XOSHIRO512_LONG_JUMP: [u64; 8] = [
+    0x11467fef8f921d28u64,
+    0xa2a819f2e79c8ea8u64,
+    0xa8299fc284b3959au64,
+    0xb4d347340ca63ee1u64,
+    0x1cb0940bedbff6ceu64,
+    0xd956c5c4fa1f8e17u64,
+    0x915e38fd4eda93bcu64,
+    0x5b3ccdfa5d7daca5u64,
+];
+
+/*
+ * The lower 512 coefficients of the (monic, degree 512) characteristic
+ * polynomial of xoshiro512's state transition matrix.
+ */
+const XOSHIRO512_CHAR_POLY: [u64; 8] = [
+    0xcf3cff0c00000001u64,
+    0x7fdc78d886f00c63u64,
+    0xf05e63fca6d7b781u64,
+    0x7a67058e7bbab6f0u64,
+    0xf11eef832e32518fu64,
+    0x51ba7c47edc758adu64,
+    0x8f2d27268ce4b20bu64,
+    0x0000500055d8b77fu64,
+];
+
+/*
+ * The jump polynomials of xoroshiro1024 from Blackman and Vigna's reference
+ * implementation. They correspond to 2^512 and 2^768 calls to next_long().
+ */
+const XOROSHIRO1024_JUMP: [u64; 16] = [
+    0x931197d8e3177f17u64,
+    0xb59422e0b9138c5fu64,
+    0xf06a6afb49d668bbu64,
+    0xacb8a6412c8a1401u64,
+    0x12304ec85f0b3468u64,
+    0xb7dfe7079209891eu64,
+    0x405b7eec77d9eb14u64,
+    0x34ead68280c44e4au64,
+    0xe0e4ba3e0ac9e366u64,
+    0x8f46eda8348905b7u64,
+    0x328bf4dbad90d6ffu64,
+    0xc8fd6fb31c9effc3u64,
+    0xe899d452d4b67652u64,
+    0x45f387286ade3205u64,
+    0x03864f454a8920bdu64,
+    0xa68fa28725b1b384u64,
+];
+const XOROSHIRO1024_LONG_JUMP: [u64; 16] = [
+    0x7374156360bbf00fu64,
+    0x4630c2efa3b3c1f6u64,
+    0x6654183a892786b1u64,
+    0x94f7bfcbfb0f1661u64,
+    0x27d8243d3d13eb2du64,
+    0x9701730f3dfb300fu64,
+    0x2f293baae6f604adu64,
+    0xa661831cb60cd8b6u64,
+    0x68280c77d9fe008cu64,
+    0x50554160f5ba9459u64,
+    0x2fc20b17ec7b2a9au64,
+    0x49189bbdc8ec9f8fu64,
+    0x92a65bca41852cc1u64,
+    0xf46820dd0509c12au64,
+    0x52b00c35fbf92185u64,
+    0x1e5b3b7f589e03c1u64,
+];
+
+/*
+ * The lower 1024 coefficients of the (monic, degree 1024) characteristic
+ * polynomial of xoroshiro1024's state transition matrix.
+ */
+pub(crate) const XOROSHIRO1024_CHAR_POLY: [u64; 16] = [
+    0x5cfeb8cc48ddb211u64,
+    0xb73e379d035a06ddu64,
+    0x17d5100a20a0350eu64,
+    0x7550223f68f98cacu64,
+    0x29d373b5c5ed3459u64,
+    0x3689b412ef70de48u64,
+    0xa1d3b6ee079a7cc6u64,
+    0x9bf0b669abd100f8u64,
+    0x955c84e105f60997u64,
+    0x6ca140c61889cdddu64,
+    0xabaf68c5fc3a0e4au64,
+    0xa46134526b83adc5u64,
+    0x0710704d05683d63u64,
+    0x580d080b44b606a2u64,
+    0x008040a0580158a1u64,
+    0x0000000000800081u64,
+];
+
+// the xoshiro256 state transition
+#[inline]
+pub(crate) fn xoshiro256(x: &mut [i64; 4]) {
+    let t = x[1] << 17;
+    x[2] ^= x[0];
+    x[3] ^= x[1];
+    x[1] ^= x[2];
+    x[0] ^= x[3];
+    x[2] ^= t;
+    x[3] = x[3].rotate_left(45);
+}
+
+// xoroshiro128 (version 1.0) state transition, used by xoroshiro128** and
+// the LXM generators
+#[inline]
+pub(crate) fn xoroshiro128(x0: &mut i64, x1: &mut i64) {
+    let q0 = *x0;
+    let q1 = *x1 ^ q0;
+    *x0 = q0.rotate_left(24) ^ q1 ^ (q1 << 16);
+    *x1 = q1.rotate_left(37);
+}
+
+// the xoroshiro128++ state transition
+#[inline]
+fn xoroshiro128pp(x: &mut [i64; 2]) {
+    let q0 = x[0];
+    let q1 = x[1] ^ q0;
+    x[0] = q0.rotate_left(49) ^ q1 ^ (q1 << 21);
+    x[1] = q1.rotate_left(28);
+}
+
+#[inline]
+fn xoroshiro128ss(x: &mut [i64; 2]) {
+    let [x0, x1] = x;
+    xoroshiro128(x0, x1);
+}
+
+// the xoshiro512 state transition
+#[inline]
+fn xoshiro512(x: &mut [i64; 8]) {
+    let t = x[1] << 11;
+    x[2] ^= x[0];
+    x[5] ^= x[1];
+    x[1] ^= x[2];
+    x[7] ^= x[3];
+    x[3] ^= x[4];
+    x[4] ^= x[5];
+    x[0] ^= x[6];
+    x[6] ^= x[7];
+    x[6] ^= t;
+    x[7] = x[7].rotate_left(21);
+}
+
+// the starstar scrambler
+#[inline]
+fn star_star(x: i64) -> i64 {
+    x.wrapping_mul(5i64).rotate_left(7).wrapping_mul(9i64)
+}
+
+// Replaces the state x by the sum of the states T^i(x) whose coefficient c_i
+// in poly is 1, i.e., jumps ahead by the number of steps that poly represents
+// (see jump_ahead).
+#[inline]
+pub(crate) fn jump_state<const N: usize>(
+    x: &mut [i64; N],
+    poly: &[u64; N],
+    step: fn(&mut [i64; N]),
+) {
+    let mut t = [0i64; N];
+    for &word in poly {
+        for b in 0..64 {
+            if (word >> b) & 1u64 != 0u64 {
+                for (t, &x) in t.iter_mut().zip(x.iter()) {
+                    *t ^= x;
+                }
+            }
+            step(x);
+        }
+    }
+    *x = t;
+}
+
+#[inline]
+fn seed_words<const N: usize>(seed: &[u8]) -> [i64; N] {
+    let mut x = [0i64; N];
+    for (i, w) in x.iter_mut().enumerate() {
+        *w = seed_word(seed, i);
+    }
+    x
+}
+
+#[inline]
+fn seeder_words<const N: usize>(seeder: &mut XorShift128Plus) -> [i64; N] {
+    let mut x = [0i64; N];
+    for w in x.iter_mut() {
+        *w = seeder.next_long();
+    }
+    x
+}
+
+//noinspection ALL
+#[inline]
+fn escape<G: PseudoRandom>(prng: &mut G) {
+    let mut l: i64 = 0i64;
+    for _ in 0..20 {
+        l = prng.next_long();
+    }
+    if l == 0i64 {
+        black_hole(l as u8);
+    }
+}
+
+// the methods and trait implementations that all members of the family
+// share, $t must provide internal_new(), jump_by() and from_state_words()
+macro_rules! impl_xoshiro {
+    ($t:ident, $seed_len:expr, $jump:expr, $long_jump:expr, $char_poly:expr, $j:literal, $lj:literal) => {
+        impl $t {
+            #[doc = concat!("Creates a new [", stringify!($t), "](", stringify!($t), ") initialized with a random seed.")]
+            #[cfg(feature = "std")]
+            #[inline]
+            pub fn new() -> Self {
+                $t::internal_new(&mut XorShift128Plus::new())
+            }
+
+            #[doc = concat!("Creates a new [", stringify!($t), "](", stringify!($t), ") initialized with the given `seed`.")]
+            #[inline]
+            pub fn new_from(seed: i64) -> Self {
+                $t::internal_new(&mut XorShift128Plus::new_from(seed))
+            }
+
+            #[doc = concat!("Advances this generator by 2<sup>", $j, "</sup> steps, which is equivalent")]
+            #[doc = concat!("to 2<sup>", $j, "</sup> calls to [next_long()](PseudoRandom::next_long). This")]
+            #[doc = concat!("can be used to generate 2<sup>", $j, "</sup> non-overlapping")]
+            /// subsequences for parallel computations.
+            #[inline]
+            pub fn jump(&mut self) {
+                self.jump_by(&$jump);
+            }
+
+            #[doc = concat!("Advances this generator by 2<sup>", $lj, "</sup> steps, which is equivalent")]
+            #[doc = concat!("to 2<sup>", $lj, "</sup> calls to [next_long()](PseudoRandom::next_long). This")]
+            /// can be used to generate starting points from each of which
+            /// [jump()](Self::jump) will generate non-overlapping subsequences for
+            /// parallel distributed computations.
+            #[inline]
+            pub fn long_jump(&mut self) {
+                self.jump_by(&$long_jump);
+            }
+
+            #[doc = concat!("Returns `n` generators whose streams are spaced 2<sup>", $j, "</sup> steps")]
+            /// apart. The first one starts at the current state of this generator and
+            /// this generator gets advanced by `n` [jumps](Self::jump) so that its own
+            /// subsequent stream doesn't overlap with any of the returned ones either.
+            pub fn jumps(&mut self, n: usize) -> Vec<Self> {
+                let mut generators = Vec::with_capacity(n);
+                for _ in 0..n {
+                    generators.push(self.clone());
+                    self.jump();
+                }
+                generators
+            }
+        }
+
+        impl Advance for $t {
+            #[inline]
+            fn advance(&mut self, n: u128) {
+                self.jump_by(&x_pow_mod(n, &$char_poly));
+            }
+        }
+
+        impl Seedable for $t {
+            type Seed = [u8; $seed_len];
+
+            #[inline]
+            fn from_seed(seed: Self::Seed) -> Self {
+                $t::from_state_words(seed_words(&seed))
+                    .unwrap_or_else(|_| Self::seed_from_u64(0u64))
+            }
+
+            #[inline]
+            fn seed_from_u64(state: u64) -> Self {
+                Self::from_seed(expand_seed(SplitMix64::new_from(state as i64)))
+            }
+
+            #[cfg(feature = "std")]
+            #[inline]
+            fn from_entropy() -> Self {
+                Self::from_seed(crate::seed::system_seed())
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl Default for $t {
+            #[inline]
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    };
+}
+
+/// 256-bit `xoshiro256++` pseudo random generator suggested by
+/// <a href=https://arxiv.org/pdf/1805.01407.pdf>David Blackman and Sebastiano
+/// Vigna (2019)</a>. This is the algorithm of JDK 17's `Xoshiro256PlusPlus`.
+///
+/// This generator has a period of 2<sup>256</sup>&nbsp;&minus;&nbsp;1.
+///
+/// This generator is 3-dimensionally equidistributed.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Xoshiro256PlusPlus {
+    // the state, at least one of the 4 elements must be nonzero
+    x: [i64; 4],
+}
+
+impl PseudoRandom for Xoshiro256PlusPlus {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        let rnd = self.x[0]
+            .wrapping_add(self.x[3])
+            .rotate_left(23)
+            .wrapping_add(self.x[0]);
+        xoshiro256(&mut self.x);
+        rnd
+    }
+}
+
+impl Xoshiro256PlusPlus {
+    /// Creates a new [Xoshiro256PlusPlus](Xoshiro256PlusPlus) that is seeded in
+    /// the same way as by JDK 17's `Xoshiro256PlusPlus(long seed)` constructor,
+    /// i.e., both produce the identical stream for the same `seed`.
+    #[inline]
+    pub fn new_java(seed: i64) -> Self {
+        Xoshiro256PlusPlus {
+            x: java_seed_words(seed),
+        }
+    }
+
+    /// Creates a new [Xoshiro256PlusPlus](Xoshiro256PlusPlus) from its complete
+    /// state `s[0]` to `s[3]` (in the notation of the reference implementation).
+    /// The result produces the same stream as the JDK 17 constructor
+    /// `Xoshiro256PlusPlus(x0, x1, x2, x3)`.
+    ///
+    /// Returns an error if all four state words are zero.
+    #[inline]
+    pub fn new_from_state(state: [i64; 4]) -> Result<Self, IllegalStateError> {
+        Self::from_state_words(state)
+    }
+
+    #[inline]
+    fn from_state_words(x: [i64; 4]) -> Result<Self, IllegalStateError> {
+        check_nonzero(&x)?;
+        Ok(Xoshiro256PlusPlus { x })
+    }
+
+    #[inline]
+    fn jump_by(&mut self, poly: &[u64; 4]) {
+        jump_state(&mut self.x, poly, xoshiro256);
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let mut instance = Xoshiro256PlusPlus {
+            x: seeder_words(seeder),
+        };
+        escape(&mut instance);
+        instance
+    }
+}
+
+impl_xoshiro!(
+    Xoshiro256PlusPlus,
+    32,
+    XOSHIRO256_JUMP,
+    XOSHIRO256_LONG_JUMP,
+    XOSHIRO256_CHAR_POLY,
+    "128",
+    "192"
+);
+
+/// 256-bit `xoshiro256+` pseudo random generator suggested by
+/// <a href=https://arxiv.org/pdf/1805.01407.pdf>David Blackman and Sebastiano
+/// Vigna (2019)</a>. It is slightly faster than the `++` and `**` scramblers
+/// and intended for generating floating point values: its lowest bits have
+/// low linear complexity, which the methods of [PseudoRandom](PseudoRandom)
+/// avoid since they all use the upper bits of [next_long()](PseudoRandom::next_long).
+///
+/// This generator has a period of 2<sup>256</sup>&nbsp;&minus;&nbsp;1.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Xoshiro256Plus {
+    // the state, at least one of the 4 elements must be nonzero
+    x: [i64; 4],
+}
+
+impl PseudoRandom for Xoshiro256Plus {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        let rnd = self.x[0].wrapping_add(self.x[3]);
+        xoshiro256(&mut self.x);
+        rnd
+    }
+}
+
+impl Xoshiro256Plus {
+    /// Creates a new [Xoshiro256Plus](Xoshiro256Plus) from its complete
+    /// state `s[0]` to `s[3]` (in the notation of the reference implementation).
+    ///
+    /// Returns an error if all four state words are zero.
+    #[inline]
+    pub fn new_from_state(state: [i64; 4]) -> Result<Self, IllegalStateError> {
+        Self::from_state_words(state)
+    }
+
+    #[inline]
+    fn from_state_words(x: [i64; 4]) -> Result<Self, IllegalStateError> {
+        check_nonzero(&x)?;
+        Ok(Xoshiro256Plus { x })
+    }
+
+    #[inline]
+    fn jump_by(&mut self, poly: &[u64; 4]) {
+        jump_state(&mut self.x, poly, xoshiro256);
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let mut instance = Xoshiro256Plus {
+            x: seeder_words(seeder),
+        };
+        escape(&mut instance);
+        instance
+    }
+}
+
+impl_xoshiro!(
+    Xoshiro256Plus,
+    32,
+    XOSHIRO256_JUMP,
+    XOSHIRO256_LONG_JUMP,
+    XOSHIRO256_CHAR_POLY,
+    "128",
+    "192"
+);
+
+/// 128-bit `xoroshiro128++` pseudo random generator suggested by
+/// <a href=https://arxiv.org/pdf/1805.01407.pdf>David Blackman and Sebastiano
+/// Vigna (2019)</a>. This is the algorithm of JDK 17's `Xoroshiro128PlusPlus`.
+/// Its small state makes it suitable where a huge number of generators is
+/// needed, e.g., one per particle.
+///
+/// This generator has a period of 2<sup>128</sup>&nbsp;&minus;&nbsp;1.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Xoroshiro128PlusPlus {
+    // the state, at least one of the 2 elements must be nonzero
+    x: [i64; 2],
+}
+
+impl PseudoRandom for Xoroshiro128PlusPlus {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        let rnd = self.x[0]
+            .wrapping_add(self.x[1])
+            .rotate_left(17)
+            .wrapping_add(self.x[0]);
+        xoroshiro128pp(&mut self.x);
+        rnd
+    }
+}
+
+impl Xoroshiro128PlusPlus {
+    /// Creates a new [Xoroshiro128PlusPlus](Xoroshiro128PlusPlus) from its
+    /// complete state `s[0]` and `s[1]` (in the notation of the reference
+    /// implementation). The result produces the same stream as the JDK 17
+    /// constructor `Xoroshiro128PlusPlus(x0, x1)`.
+    ///
+    /// Returns an error if both state words are zero.
+    #[inline]
+    pub fn new_from_state(state: [i64; 2]) -> Result<Self, IllegalStateError> {
+        Self::from_state_words(state)
+    }
+
+    #[inline]
+    fn from_state_words(x: [i64; 2]) -> Result<Self, IllegalStateError> {
+        check_nonzero(&x)?;
+        Ok(Xoroshiro128PlusPlus { x })
+    }
+
+    #[inline]
+    fn jump_by(&mut self, poly: &[u64; 2]) {
+        jump_state(&mut self.x, poly, xoroshiro128pp);
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let mut instance = Xoroshiro128PlusPlus {
+            x: seeder_words(seeder),
+        };
+        escape(&mut instance);
+        instance
+    }
+}
+
+impl_xoshiro!(
+    Xoroshiro128PlusPlus,
+    16,
+    XOROSHIRO128PP_JUMP,
+    XOROSHIRO128PP_LONG_JUMP,
+    XOROSHIRO128PP_CHAR_POLY,
+    "64",
+    "96"
+);
+
+/// 128-bit `xoroshiro128**` pseudo random generator suggested by
+/// <a href=https://arxiv.org/pdf/1805.01407.pdf>David Blackman and Sebastiano
+/// Vigna (2019)</a>. Its small state makes it suitable where a huge number of
+/// generators is needed, e.g., one per particle.
+///
+/// This generator has a period of 2<sup>128</sup>&nbsp;&minus;&nbsp;1.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Xoroshiro128StarStar {
+    // the state, at least one of the 2 elements must be nonzero
+    x: [i64; 2],
+}
+
+impl PseudoRandom for Xoroshiro128StarStar {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        let rnd = star_star(self.x[0]);
+        xoroshiro128ss(&mut self.x);
+        rnd
+    }
+}
+
+impl Xoroshiro128StarStar {
+    /// Creates a new [Xoroshiro128StarStar](Xoroshiro128StarStar) from its
+    /// complete state `s[0]` and `s[1]` (in the notation of the reference
+    /// implementation).
+    ///
+    /// Returns an error if both state words are zero.
+    #[inline]
+    pub fn new_from_state(state: [i64; 2]) -> Result<Self, IllegalStateError> {
+        Self::from_state_words(state)
+    }
+
+    #[inline]
+    fn from_state_words(x: [i64; 2]) -> Result<Self, IllegalStateError> {
+        check_nonzero(&x)?;
+        Ok(Xoroshiro128StarStar { x })
+    }
+
+    #[inline]
+    fn jump_by(&mut self, poly: &[u64; 2]) {
+        jump_state(&mut self.x, poly, xoroshiro128ss);
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let mut instance = Xoroshiro128StarStar {
+            x: seeder_words(seeder),
+        };
+        escape(&mut instance);
+        instance
+    }
+}
+
+impl_xoshiro!(
+    Xoroshiro128StarStar,
+    16,
+    XOROSHIRO128_JUMP,
+    XOROSHIRO128_LONG_JUMP,
+    XOROSHIRO128_CHAR_POLY,
+    "64",
+    "96"
+);
+
+/// 512-bit `xoshiro512**` pseudo random generator suggested by
+/// <a href=https://arxiv.org/pdf/1805.01407.pdf>David Blackman and Sebastiano
+/// Vigna (2019)</a>. Its large state space makes it suitable for very long
+/// simulations that need many non-overlapping streams.
+///
+/// This generator has a period of 2<sup>512</sup>&nbsp;&minus;&nbsp;1.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Xoshiro512StarStar {
+    // the state, at least one of the 8 elements must be nonzero
+    x: [i64; 8],
+}
+
+impl PseudoRandom for Xoshiro512StarStar {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        let rnd = star_star(self.x[1]);
+        xoshiro512(&mut self.x);
+        rnd
+    }
+}
+
+impl Xoshiro512StarStar {
+    /// Creates a new [Xoshiro512StarStar](Xoshiro512StarStar) from its complete
+    /// state `s[0]` to `s[7]` (in the notation of the reference implementation).
+    ///
+    /// Returns an error if all eight state words are zero.
+    #[inline]
+    pub fn new_from_state(state: [i64; 8]) -> Result<Self, IllegalStateError> {
+        Self::from_state_words(state)
+    }
+
+    #[inline]
+    fn from_state_words(x: [i64; 8]) -> Result<Self, IllegalStateError> {
+        check_nonzero(&x)?;
+        Ok(Xoshiro512StarStar { x })
+    }
+
+    #[inline]
+    fn jump_by(&mut self, poly: &[u64; 8]) {
+        jump_state(&mut self.x, poly, xoshiro512);
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let mut instance = Xoshiro512StarStar {
+            x: seeder_words(seeder),
+        };
+        escape(&mut instance);
+        instance
+    }
+}
+
+impl_xoshiro!(
+    Xoshiro512StarStar,
+    64,
+    XOSHIRO512_JUMP,
+    XOSHIRO512_LONG_JUMP,
+    XOSHIRO512_CHAR_POLY,
+    "256",
+    "384"
+);
+
+/// 1024-bit `xoroshiro1024**` pseudo random generator suggested by
+/// <a href=https://arxiv.org/pdf/1805.01407.pdf>David Blackman and Sebastiano
+/// Vigna (2019)</a>. Its large state space makes it suitable for very long
+/// simulations that need many non-overlapping streams.
+///
+/// This generator has a period of 2<sup>1024</sup>&nbsp;&minus;&nbsp;1.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Xoroshiro1024StarStar {
+    // the state, at least one of the 16 elements must be nonzero
+    x: [i64; 16],
+    // the rotating pointer into the array x
+    p: usize,
+}
+
+impl PseudoRandom for Xoroshiro1024StarStar {
+    #[inline]
+    fn next_long(&mut self) -> i64 {
+        let rnd = star_star(self.x[(self.p + 1) & 15]);
+        self.step();
+        rnd
+    }
+}
+
+impl Xoroshiro1024StarStar {
+    /// Creates a new [Xoroshiro1024StarStar](Xoroshiro1024StarStar) from its
+    /// complete state `s[0]` to `s[15]` and the index `p` (in the notation of
+    /// the reference implementation, where `p` starts at `0`). `p` is taken
+    /// modulo 16.
+    ///
+    /// Returns an error if all 16 state words are zero.
+    #[inline]
+    pub fn new_from_state(state: [i64; 16], p: usize) -> Result<Self, IllegalStateError> {
+        check_nonzero(&state)?;
+        Ok(Xoroshiro1024StarStar {
+            x: state,
+            p: p & 15,
+        })
+    }
+
+    #[inline]
+    fn from_state_words(x: [i64; 16]) -> Result<Self, IllegalStateError> {
+        Self::new_from_state(x, 0usize)
+    }
+
+    #[inline]
+    fn step(&mut self) {
+        let q = self.p;
+        self.p = (self.p + 1) & 15;
+        let s0 = self.x[self.p];
+        let s15 = self.x[q] ^ s0;
+        self.x[q] = s0.rotate_left(25) ^ s15 ^ (s15 << 27);
+        self.x[self.p] = s15.rotate_left(36);
+    }
+
+    #[inline]
+    fn jump_by(&mut self, poly: &[u64; 16]) {
+        let mut t = [0i64; 16];
+        for &word in poly {
+            for b in 0..64 {
+                if (word >> b) & 1u64 != 0u64 {
+                    for (j, t) in t.iter_mut().enumerate() {
+                        *t ^= self.x[(j + self.p) & 15];
+                    }
+                }
+                self.step();
+            }
+        }
+        for (j, &t) in t.iter().enumerate() {
+            self.x[(j + self.p) & 15] = t;
+        }
+    }
+
+    #[inline]
+    fn internal_new(seeder: &mut XorShift128Plus) -> Self {
+        let mut instance = Xoroshiro1024StarStar {
+            x: seeder_words(seeder),
+            p: 0usize,
+        };
+        escape(&mut instance);
+        instance
+    }
+}
+
+impl_xoshiro!(
+    Xoroshiro1024StarStar,
+    128,
+    XOROSHIRO1024_JUMP,
+    XOROSHIRO1024_LONG_JUMP,
+    XOROSHIRO1024_CHAR_POLY,
+    "512",
+    "768"
+);
+
+#[cfg(test)]
+mod xoshiro_tests {
+    use super::*;
+    use crate::test_util::check_advance;
+
+    // The expected values in these tests have been produced by Blackman and
+    // Vigna's reference implementations in C (next(), jump() and long_jump()),
+    // the ++ variants agree with JDK 17's Xoshiro256PlusPlus and
+    // Xoroshiro128PlusPlus (nextLong(), jump() and leap()).
+    fn check_reference<G: PseudoRandom>(
+        mut prng: G,
+        jump: fn(&mut G),
+        long_jump: fn(&mut G),
+        expected: [i64; 10],
+    ) {
+        for &e in &expected[0..4] {
+            assert_eq!(prng.next_long(), e);
+        }
+        jump(&mut prng);
+        for &e in &expected[4..7] {
+            assert_eq!(prng.next_long(), e);
+        }
+        long_jump(&mut prng);
+        for &e in &expected[7..10] {
+            assert_eq!(prng.next_long(), e);
+        }
+    }
+
+    // jump() must be equivalent to advancing twice by half the jump distance
+    fn check_jump_advance<G: PseudoRandom + Advance + Clone>(
+        prng: G,
+        jump: fn(&mut G),
+        half: u128,
+    ) {
+        let mut prng1 = prng.clone();
+        let mut prng2 = prng;
+        prng1.next_long();
+        jump(&mut prng1);
+        prng2.advance(half);
+        prng2.next_long();
+        prng2.advance(half);
+        for _ in 0..16 {
+            assert_eq!(prng1.next_long(), prng2.next_long());
+        }
+    }
+
+    #[test]
+    fn test_xoshiro256_plus_plus() {
+        let prng = Xoshiro256PlusPlus::new_from_state([1i64, 2i64, 3i64, 4i64]).unwrap();
+        check_reference(
+            prng,
+            Xoshiro256PlusPlus::jump,
+            Xoshiro256PlusPlus::long_jump,
+            [
+                41943041i64,
+                58720359i64,
+                3588806011781223i64,
+                3591011842654386i64,
+                -7607744242089052400i64,
+                8680420094678800874i64,
+                -8876688430425606806i64,
+                1701409271672714996i64,
+                7229965972965062926i64,
+                2140690761664815708i64,
+            ],
+        );
+        assert_eq!(
+            Xoshiro256PlusPlus::new_from_state([0i64; 4]).unwrap_err(),
+            IllegalStateError::AllZero
+        );
+        let prng = Xoshiro256PlusPlus::new_from(42i64);
+        check_jump_advance(prng, Xoshiro256PlusPlus::jump, 1u128 << 127);
+    }
+
+    #[test]
+    fn test_xoshiro256_plus_plus_java() {
+        // reference values from JDK 17's Xoshiro256PlusPlus(long)
+        let mut prng = Xoshiro256PlusPlus::new_java(42i64);
+        assert_eq!(prng.next_long(), -5479502503263141524i64);
+        assert_eq!(prng.next_long(), 6190540527256294342i64);
+        assert_eq!(prng.next_long(), 2092709113264991754i64);
+        let mut prng = Xoshiro256PlusPlus::new_java(-7i64);
+        assert_eq!(prng.next_long(), -7676520334733627824i64);
+        assert_eq!(prng.next_long(), -7971069473351669921i64);
+        assert_eq!(prng.next_long(), 3316650014066664788i64);
+    }
+
+    #[test]
+    fn test_xoshiro256_plus() {
+        let prng = Xoshiro256Plus::new_from_state([1i64, 2i64, 3i64, 4i64]).unwrap();
+        check_reference(
+            prng,
+            Xoshiro256Plus::jump,
+            Xoshiro256Plus::long_jump,
+            [
+                5i64,
+                211106232532999i64,
+                211106635186183i64,
+                -9222985008358882558i64,
+                -3629349581772206866i64,
+                2279703207880370032i64,
+                -7520524476296389853i64,
+                6798233631567235781i64,
+                -6524749066552067369i64,
+                -6309789987244224923i64,
+            ],
+        );
+        let mut prng = Xoshiro256Plus::new_from(42i64);
+        for _ in 0..100 {
+            let d = prng.next_double();
+            assert!((0.0f64..1.0f64).contains(&d));
+        }
+    }
+
+    #[test]
+    fn test_xoroshiro128_plus_plus() {
+        let prng = Xoroshiro128PlusPlus::new_from_state([1i64, 2i64]).unwrap();
+        check_reference(
+            prng,
+            Xoroshiro128PlusPlus::jump,
+            Xoroshiro128PlusPlus::long_jump,
+            [
+                393217i64,
+                669327710093319i64,
+                1732421326133921491i64,
+                -7051953992050424633i64,
+                1545814713924780798i64,
+                2325680523787453464i64,
+                6135803246428234680i64,
+                -4143588489257071269i64,
+                -1841185874565590711i64,
+                -2771016486614259182i64,
+            ],
+        );
+        assert_eq!(
+            Xoroshiro128PlusPlus::new_from_state([0i64; 2]).unwrap_err(),
+            IllegalStateError::AllZero
+        );
+        let prng = Xoroshiro128PlusPlus::new_from(42i64);
+        check_jump_advance(prng.clone(), Xoroshiro128PlusPlus::jump, 1u128 << 63);
+        check_jump_advance(prng, Xoroshiro128PlusPlus::long_jump, 1u128 << 95);
+    }
+
+    #[test]
+    fn test_xoroshiro128_star_star() {
+        let prng = Xoroshiro128StarStar::new_from_state([1i64, 2i64]).unwrap();
+        check_reference(
+            prng,
+            Xoroshiro128StarStar::jump,
+            Xoroshiro128StarStar::long_jump,
+            [
+                5760i64,
+                97769243520i64,
+                -8739881946231848064i64,
+                -9223296562248771662i64,
+                -3810808836283960885i64,
+                4722186862080112093i64,
+                -2286405885093131372i64,
+                -7716271799845273963i64,
+                3374557779736524915i64,
+                -7729297261680235742i64,
+            ],
+        );
+        let prng = Xoroshiro128StarStar::new_from(42i64);
+        check_jump_advance(prng.clone(), Xoroshiro128StarStar::jump, 1u128 << 63);
+        check_jump_advance(prng, Xoroshiro128StarStar::long_jump, 1u128 << 95);
+    }
+
+    #[test]
+    fn test_xoshiro512_star_star() {
+        let state = [1i64, 2i64, 3i64, 4i64, 5i64, 6i64, 7i64, 8i64];
+        let prng = Xoshiro512StarStar::new_from_state(state).unwrap();
+        check_reference(
+            prng,
+            Xoshiro512StarStar::jump,
+            Xoshiro512StarStar::long_jump,
+            [
+                11520i64,
+                0i64,
+                23040i64,
+                23667840i64,
+                6173576884829712160i64,
+                -8483855117860564568i64,
+                -7392186834538897185i64,
+                804384661824251885i64,
+                -5742484502167332569i64,
+                7702364009836986944i64,
+            ],
+        );
+        assert_eq!(
+            Xoshiro512StarStar::new_from_state([0i64; 8]).unwrap_err(),
+            IllegalStateError::AllZero
+        );
+        check_advance(Xoshiro512StarStar::new_from(42i64));
+    }
+
+    #[test]
+    fn test_xoroshiro1024_star_star() {
+        let mut state = [0i64; 16];
+        for (i, x) in state.iter_mut().enumerate() {
+            *x = i as i64 + 1i64;
+        }
+        let prng = Xoroshiro1024StarStar::new_from_state(state, 0usize).unwrap();
+        check_reference(
+            prng,
+            Xoroshiro1024StarStar::jump,
+            Xoroshiro1024StarStar::long_jump,
+            [
+                11520i64,
+                17280i64,
+                23040i64,
+                28800i64,
+                -5193837169099272600i64,
+                3265204282636674804i64,
+                -6781011381955265442i64,
+                3072640343292867776i64,
+                -1542098953422116123i64,
+                -4180776781543599721i64,
+            ],
+        );
+        assert_eq!(
+            Xoroshiro1024StarStar::new_from_state([0i64; 16], 3usize).unwrap_err(),
+            IllegalStateError::AllZero
+        );
+        check_advance(Xoroshiro1024StarStar::new_from(42i64));
+    }
+
+    #[test]
+    fn test_jumps() {
+        let mut prng = Xoroshiro128PlusPlus::new_from(7i64);
+        let mut expected = prng.clone();
+        let generators = prng.jumps(3);
+        assert_eq!(generators.len(), 3);
+        for mut g in generators {
+            assert_eq!(g.next_long(), expected.clone().next_long());
+            expected.jump();
+        }
+        assert_eq!(prng.next_long(), expected.next_long());
+    }
+}
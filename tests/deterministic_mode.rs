@@ -0,0 +1,93 @@
+// Copyright 2022 Stefan Zobel
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+// The deterministic mode is process-wide and can't be left again. That's why
+// these checks run in their own test binary and in a single #[test] function.
+#![cfg(feature = "std")]
+
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+use rnd::pseudo_random::{
+    PseudoRandom, Seedable, Stc64, ThreadLocal, ThreadLocalPrng, XoShiRo256StarStar,
+};
+use rnd::{global_seed, set_global_seed};
+
+fn draw() -> Vec<i64> {
+    let mut rng1 = Stc64::new();
+    let mut rng2 = Stc64::from_entropy();
+    let mut rng3 = ThreadLocalPrng::get();
+    let mut rng4 = ThreadLocal::<XoShiRo256StarStar>::get();
+    vec![
+        rng1.next_long(),
+        rng2.next_long(),
+        rng3.next_long(),
+        rng4.next_long(),
+        rng1.next_long(),
+    ]
+}
+
+fn draw_on_thread(name: &str) -> Vec<i64> {
+    thread::Builder::new()
+        .name(name.to_string())
+        .spawn(draw)
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+// runs draw() on two threads with the same name that are alive at the same time
+fn draw_on_pool(name: &str) -> Vec<Vec<i64>> {
+    let barrier = Arc::new(Barrier::new(2));
+    let workers: Vec<_> = (0..2)
+        .map(|_| {
+            let barrier = Arc::clone(&barrier);
+            thread::Builder::new()
+                .name(name.to_string())
+                .spawn(move || {
+                    barrier.wait();
+                    draw()
+                })
+                .unwrap()
+        })
+        .collect();
+    let mut values: Vec<Vec<i64>> = workers.into_iter().map(|w| w.join().unwrap()).collect();
+    // the order in which the two threads request their seeds is arbitrary
+    values.sort();
+    values
+}
+
+#[test]
+fn test_deterministic_mode() {
+    set_global_seed(12345u64);
+    assert_eq!(global_seed(), Some(12345u64));
+    let fst = draw_on_thread("rnd-worker-1");
+    let snd = draw_on_thread("rnd-worker-1");
+    let other = draw_on_thread("rnd-worker-2");
+    let pool = draw_on_pool("rnd-pool");
+    assert_ne!(fst, snd);
+    assert_ne!(fst, other);
+    assert_ne!(pool[0], pool[1]);
+    assert_ne!(fst[0], fst[1]);
+    assert_ne!(fst[0], fst[2]);
+    assert_ne!(fst[2], fst[3]);
+
+    // the same root reproduces the same streams
+    set_global_seed(12345u64);
+    assert_eq!(fst, draw_on_thread("rnd-worker-1"));
+    assert_eq!(snd, draw_on_thread("rnd-worker-1"));
+    assert_eq!(other, draw_on_thread("rnd-worker-2"));
+    assert_eq!(pool, draw_on_pool("rnd-pool"));
+
+    // a new root starts the per-thread streams from scratch
+    set_global_seed(77u64);
+    assert_eq!(global_seed(), Some(77u64));
+    let third = draw_on_thread("rnd-worker-1");
+    assert_ne!(fst, third);
+    set_global_seed(77u64);
+    assert_eq!(third, draw_on_thread("rnd-worker-1"));
+}
@@ -0,0 +1,95 @@
+// Copyright 2022 Stefan Zobel
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+// Forking a multi-threaded process is only safe if no other thread holds a
+// lock at that moment. That's why these checks run in their own test binary
+// and in a single #[test] function.
+#![cfg(all(unix, feature = "std"))]
+
+use std::convert::TryInto;
+
+use rnd::pseudo_random::{PseudoRandom, Stc64, ThreadLocal, ThreadLocalPrng, XoShiRo256StarStar};
+
+fn draw() -> [i64; 3] {
+    [
+        ThreadLocalPrng::get().next_long(),
+        ThreadLocal::<XoShiRo256StarStar>::get().next_long(),
+        Stc64::new().next_long(),
+    ]
+}
+
+// runs draw() in a forked child and returns the child's values
+fn draw_in_child() -> [i64; 3] {
+    let mut fds = [0i32; 2];
+    unsafe {
+        assert_eq!(libc::pipe(fds.as_mut_ptr()), 0);
+        let pid = libc::fork();
+        assert!(pid >= 0);
+        if pid == 0 {
+            let values = draw();
+            let mut bytes = [0u8; 24];
+            for (chunk, v) in bytes.chunks_mut(8).zip(values.iter()) {
+                chunk.copy_from_slice(&v.to_le_bytes());
+            }
+            libc::write(fds[1], bytes.as_ptr().cast(), bytes.len());
+            libc::_exit(0);
+        }
+        libc::close(fds[1]);
+        let mut bytes = [0u8; 24];
+        let mut read = 0usize;
+        while read < bytes.len() {
+            let n = libc::read(
+                fds[0],
+                bytes[read..].as_mut_ptr().cast(),
+                bytes.len() - read,
+            );
+            assert!(n > 0);
+            read += n as usize;
+        }
+        libc::close(fds[0]);
+        let mut status = 0i32;
+        assert_eq!(libc::waitpid(pid, &mut status, 0), pid);
+        let mut values = [0i64; 3];
+        for (v, chunk) in values.iter_mut().zip(bytes.chunks(8)) {
+            *v = i64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        values
+    }
+}
+
+#[test]
+fn test_fork() {
+    // initialize the global seed state and the thread-local generators
+    draw();
+
+    let parent_state = (
+        ThreadLocalPrng::snapshot(),
+        ThreadLocal::<XoShiRo256StarStar>::snapshot(),
+    );
+    let child1 = draw_in_child();
+    let child2 = draw_in_child();
+    let parent = draw();
+    println!("parent: {:?}", parent);
+    println!("child1: {:?}", child1);
+    println!("child2: {:?}", child2);
+    for i in 0..3 {
+        assert_ne!(parent[i], child1[i]);
+        assert_ne!(parent[i], child2[i]);
+        assert_ne!(child1[i], child2[i]);
+    }
+
+    // opt-out: the child continues with the parent's streams
+    rnd::set_reseed_on_fork(false);
+    ThreadLocalPrng::restore(parent_state.0);
+    ThreadLocal::<XoShiRo256StarStar>::restore(parent_state.1);
+    let child = draw_in_child();
+    let parent = draw();
+    assert_eq!(parent[0], child[0]);
+    assert_eq!(parent[1], child[1]);
+    assert_eq!(parent[2], child[2]);
+    rnd::set_reseed_on_fork(true);
+}